@@ -0,0 +1,687 @@
+//! Atom 1.0 export and import
+//!
+//! The feed becomes an Atom `feed` and its items `entry`s. Links are
+//! written with `rel="alternate"` for `url` and `home_page_url`,
+//! `rel="related"` for `external_url`, `rel="enclosure"` for attachments,
+//! `rel="self"` for `feed_url`, `rel="next"` for `next_url` and
+//! `rel="hub"` for hubs. A feed's `icon` becomes the Atom `logo`, and its
+//! `favicon` the Atom `icon`.
+//!
+//! The Atom feed `id` is the `feed_url`, or the `home_page_url` if there
+//! is none. On import, a feed without a `rel="self"` link takes its
+//! `feed_url` from an `id` that is an HTTP(S) URL.
+//!
+//! Content maps as follows, in both directions:
+//!
+//! * `Content::Html` is `<content type="html">`
+//! * `Content::Text` is `<content type="text">`
+//! * `Content::Both` is `<content type="html">`, with the text as the
+//!   `<summary>`. If the item also has a `summary`, that is written
+//!   instead and the text is reported as unmapped.
+//!
+//! So on import, an entry with HTML content and a summary gets
+//! `Content::Both` and no `summary`, while an entry with text content and
+//! a summary keeps both. An entry's `updated` date becomes `date_modified`
+//! unless it equals `published`, since Atom requires it and export fills
+//! it in from `date_published`.
+//!
+//! Anything that can't be mapped is left out and listed in the returned
+//! `ConversionReport`.
+
+use std::io::{Read, Write};
+
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+use errors::*;
+use convert::{self, ConversionReport};
+use date;
+use dom::{self, Element};
+use feed::{Feed, Author, Attachment, Hub};
+use item::{Content, Item};
+use version::Version;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+
+/// Written for `updated` when there is no date to take it from
+const EPOCH: &str = "1970-01-01T00:00:00+00:00";
+
+/// Writes a feed as an Atom 1.0 document
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::{Feed, Item};
+/// # fn main() {
+/// let feed = Feed::builder()
+///                 .title("my feed")
+///                 .feed_url("https://example.com/feed.json")
+///                 .item(Item::builder()
+///                         .id("1")
+///                         .title("hello")
+///                         .content_text("Hello, World!")
+///                         .date_published("2017-04-26T01:09:45+00:00")
+///                         .build()
+///                         .unwrap())
+///                 .build()
+///                 .unwrap();
+/// let (atom, report) = jsonfeed::atom::to_string(&feed).unwrap();
+/// assert!(atom.contains("<content type=\"text\">Hello, World!</content>"));
+/// assert!(report.is_empty());
+/// # }
+/// ```
+pub fn to_string(feed: &Feed) -> Result<(String, ConversionReport)> {
+    let mut report = ConversionReport::default();
+    let mut w = EmitterConfig::new().perform_indent(true).create_writer(Vec::new());
+    write_feed(&mut w, feed, &mut report)?;
+    let atom = String::from_utf8(w.into_inner()).expect("xml-rs writes UTF-8");
+    Ok((atom, report))
+}
+
+fn write_feed<W: Write>(w: &mut EventWriter<W>, feed: &Feed, report: &mut ConversionReport) -> Result<()> {
+    let mut start = XmlEvent::start_element("feed").default_ns(ATOM_NS);
+    if let Some(ref language) = feed.language {
+        start = start.attr("xml:lang", language);
+    }
+    w.write(start)?;
+
+    let id = match (feed.feed_url.as_ref(), feed.home_page_url.as_ref()) {
+        (Some(url), _) | (None, Some(url)) => &url[..],
+        (None, None) => {
+            report.lossy("feed_url", "Atom requires a feed id, written empty");
+            ""
+        },
+    };
+    dom::write_text(w, "id", id)?;
+    dom::write_text(w, "title", &feed.title)?;
+    if let Some(ref description) = feed.description {
+        dom::write_text(w, "subtitle", description)?;
+    }
+    let updated = feed.items.iter()
+        .flat_map(|item| item.date_modified.iter().chain(item.date_published.iter()))
+        .max_by_key(|d| date::timestamp(d));
+    match updated {
+        Some(updated) => dom::write_text(w, "updated", updated)?,
+        None => {
+            report.lossy("updated", "Atom requires an updated date, written as the Unix epoch");
+            dom::write_text(w, "updated", EPOCH)?;
+        },
+    }
+    if let Some(ref url) = feed.home_page_url {
+        link(w, "alternate", url)?;
+    }
+    if let Some(ref url) = feed.feed_url {
+        link(w, "self", url)?;
+    }
+    if let Some(ref url) = feed.next_url {
+        link(w, "next", url)?;
+    }
+    if let Some(ref hubs) = feed.hubs {
+        for (i, hub) in hubs.iter().enumerate() {
+            if !hub.hub_type().eq_ignore_ascii_case("websub") {
+                report.lossy(format!("hubs[{}].type", i), "Atom hub links are always WebSub");
+            }
+            link(w, "hub", hub.url())?;
+            report.unmapped_extensions(&format!("hubs[{}].", i), &hub.extensions);
+        }
+    }
+    if let Some(ref favicon) = feed.favicon {
+        dom::write_text(w, "icon", favicon)?;
+    }
+    if let Some(ref icon) = feed.icon {
+        dom::write_text(w, "logo", icon)?;
+    }
+    authors(w, "", &feed.author, &feed.authors, report)?;
+
+    if feed.user_comment.is_some() {
+        report.unmapped("user_comment");
+    }
+    if feed.expired.is_some() {
+        report.unmapped("expired");
+    }
+    report.unmapped_extensions("", &feed.extensions);
+
+    for (i, item) in feed.items.iter().enumerate() {
+        write_entry(w, &format!("items[{}].", i), item, report)?;
+    }
+
+    w.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+fn write_entry<W: Write>(w: &mut EventWriter<W>,
+                         prefix: &str,
+                         item: &Item,
+                         report: &mut ConversionReport) -> Result<()>
+{
+    let mut start = XmlEvent::start_element("entry");
+    if let Some(ref language) = item.language {
+        start = start.attr("xml:lang", language);
+    }
+    w.write(start)?;
+
+    dom::write_text(w, "id", &item.id)?;
+    match item.title {
+        Some(ref title) => dom::write_text(w, "title", title)?,
+        None => {
+            report.lossy(format!("{}title", prefix), "Atom requires an entry title, written empty");
+            dom::write_text(w, "title", "")?;
+        },
+    }
+    match item.date_modified.as_ref().or(item.date_published.as_ref()) {
+        Some(updated) => dom::write_text(w, "updated", updated)?,
+        None => {
+            report.lossy(format!("{}updated", prefix),
+                         "Atom requires an updated date, written as the Unix epoch");
+            dom::write_text(w, "updated", EPOCH)?;
+        },
+    }
+    if let Some(ref published) = item.date_published {
+        dom::write_text(w, "published", published)?;
+    }
+    if let Some(ref url) = item.url {
+        link(w, "alternate", url)?;
+    }
+    if let Some(ref url) = item.external_url {
+        link(w, "related", url)?;
+    }
+    if let Some(ref attachments) = item.attachments {
+        for (j, attachment) in attachments.iter().enumerate() {
+            enclosure(w, &format!("{}attachments[{}]", prefix, j), attachment, report)?;
+        }
+    }
+    authors(w, prefix, &item.author, &item.authors, report)?;
+    if let Some(ref tags) = item.tags {
+        for tag in tags {
+            w.write(XmlEvent::start_element("category").attr("term", tag))?;
+            w.write(XmlEvent::end_element())?;
+        }
+    }
+
+    let summary = match (item.summary.as_ref(), &item.content) {
+        (Some(summary), Content::Both(..)) => {
+            report.unmapped(format!("{}content_text", prefix));
+            Some(&summary[..])
+        },
+        (Some(summary), _) => Some(&summary[..]),
+        (None, Content::Both(_, text)) => Some(&text[..]),
+        (None, _) => None,
+    };
+    if let Some(summary) = summary {
+        dom::write_text(w, "summary", summary)?;
+    }
+    let (content_type, content) = match item.content {
+        Content::Html(ref html) | Content::Both(ref html, _) => ("html", html),
+        Content::Text(ref text) => ("text", text),
+    };
+    w.write(XmlEvent::start_element("content").attr("type", content_type))?;
+    w.write(XmlEvent::characters(content))?;
+    w.write(XmlEvent::end_element())?;
+
+    if item.image.is_some() {
+        report.unmapped(format!("{}image", prefix));
+    }
+    if item.banner_image.is_some() {
+        report.unmapped(format!("{}banner_image", prefix));
+    }
+    report.unmapped_extensions(prefix, &item.extensions);
+
+    w.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+fn enclosure<W: Write>(w: &mut EventWriter<W>,
+                       path: &str,
+                       attachment: &Attachment,
+                       report: &mut ConversionReport) -> Result<()>
+{
+    let length = attachment.size_in_bytes.map(|size| size.to_string());
+    let mut start = XmlEvent::start_element("link")
+        .attr("rel", "enclosure")
+        .attr("href", &attachment.url)
+        .attr("type", &attachment.mime_type);
+    if let Some(ref length) = length {
+        start = start.attr("length", length);
+    }
+    if let Some(ref title) = attachment.title {
+        start = start.attr("title", title);
+    }
+    w.write(start)?;
+    w.write(XmlEvent::end_element())?;
+
+    if attachment.duration_in_seconds.is_some() {
+        report.unmapped(format!("{}.duration_in_seconds", path));
+    }
+    report.unmapped_extensions(&format!("{}.", path), &attachment.extensions);
+    Ok(())
+}
+
+fn authors<W: Write>(w: &mut EventWriter<W>,
+                     prefix: &str,
+                     author: &Option<Author>,
+                     authors: &Option<Vec<Author>>,
+                     report: &mut ConversionReport) -> Result<()>
+{
+    let mut all = vec![];
+    if let Some(ref author) = *author {
+        all.push((format!("{}author", prefix), author));
+    }
+    if let Some(ref authors) = *authors {
+        for (i, author) in authors.iter().enumerate() {
+            all.push((format!("{}authors[{}]", prefix, i), author));
+        }
+    }
+
+    let mut written: Vec<&Author> = vec![];
+    for (path, author) in all {
+        // `upgrade_to_1_1` can leave the same author in both fields
+        if written.contains(&author) {
+            continue;
+        }
+        written.push(author);
+
+        w.write(XmlEvent::start_element("author"))?;
+        match author.name {
+            Some(ref name) => dom::write_text(w, "name", name)?,
+            None => {
+                report.lossy(format!("{}.name", path), "Atom requires an author name, written empty");
+                dom::write_text(w, "name", "")?;
+            },
+        }
+        if let Some(ref url) = author.url {
+            dom::write_text(w, "uri", url)?;
+        }
+        w.write(XmlEvent::end_element())?;
+
+        if author.avatar.is_some() {
+            report.unmapped(format!("{}.avatar", path));
+        }
+        report.unmapped_extensions(&format!("{}.", path), &author.extensions);
+    }
+    Ok(())
+}
+
+fn link<W: Write>(w: &mut EventWriter<W>, rel: &str, href: &str) -> Result<()> {
+    w.write(XmlEvent::start_element("link").attr("rel", rel).attr("href", href))?;
+    w.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+/// Reads an Atom 1.0 document into a feed
+///
+/// Entries without an `id` get their alternate link as id or, failing
+/// that, a hash of their title, content and dates, so importing the same
+/// document twice gives the same ids.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # fn main() {
+/// let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+///     <title>my feed</title>
+///     <entry><id>1</id><content type="html">&lt;p&gt;Hello&lt;/p&gt;</content></entry>
+/// </feed>"#;
+/// let (feed, _) = jsonfeed::atom::from_reader(atom.as_bytes()).unwrap();
+/// assert_eq!(feed.items[0].content.html(), Some("<p>Hello</p>"));
+/// # }
+/// ```
+pub fn from_reader<R: Read>(reader: R) -> Result<(Feed, ConversionReport)> {
+    let root = dom::parse(reader)?;
+    if !root.is(Some(ATOM_NS), "feed") {
+        return Err(Error::invalid_value(format!("expected an Atom document, found <{}>", root.qualified), ""));
+    }
+
+    let mut report = ConversionReport::default();
+    let mut feed = Feed {
+        version: Version::V1_1,
+        language: root.attr("xml:lang").map(|s| s.to_string()),
+        ..Feed::default()
+    };
+    let mut authors = vec![];
+    let mut hubs = vec![];
+    let mut title = None;
+    let mut id = None;
+    for child in &root.children {
+        let path = format!("feed.{}", child.qualified);
+        if child.namespace.as_ref().map(|s| &s[..]) != Some(ATOM_NS) {
+            report.unmapped(path);
+            continue;
+        }
+        match &child.name[..] {
+            "entry" => {
+                let path = format!("feed.entry[{}]", feed.items.len());
+                let item = read_entry(child, &path, &mut report);
+                feed.items.push(item);
+            },
+            "id" => id = child.trimmed(),
+            "title" => title = child.trimmed(),
+            "subtitle" => feed.description = child.trimmed(),
+            "icon" => feed.favicon = child.trimmed(),
+            "logo" => feed.icon = child.trimmed(),
+            "author" => authors.push(read_author(child, &path, &mut report)),
+            "link" => {
+                let href = child.attr("href").map(|s| s.to_string());
+                match child.attr("rel").unwrap_or("alternate") {
+                    "alternate" if feed.home_page_url.is_none() => feed.home_page_url = href,
+                    "self" if feed.feed_url.is_none() => feed.feed_url = href,
+                    "next" if feed.next_url.is_none() => feed.next_url = href,
+                    "hub" => hubs.extend(href.map(|href| Hub::new("WebSub", href))),
+                    rel => report.unmapped(format!("{}[rel={}]", path, rel)),
+                }
+            },
+            _ => report.unmapped(path),
+        }
+    }
+
+    feed.title = title.unwrap_or_else(|| {
+        report.lossy("feed.title", "the feed has no title, written empty");
+        String::new()
+    });
+    if let Some(id) = id {
+        // export writes the home page as the id of a feed without a feed_url
+        let known = feed.feed_url.as_ref() == Some(&id) || feed.home_page_url.as_ref() == Some(&id);
+        if !known {
+            if feed.feed_url.is_none() && (id.starts_with("http://") || id.starts_with("https://")) {
+                feed.feed_url = Some(id);
+            } else {
+                report.unmapped("feed.id");
+            }
+        }
+    }
+    if !authors.is_empty() {
+        feed.authors = Some(authors);
+    }
+    if !hubs.is_empty() {
+        feed.hubs = Some(hubs);
+    }
+    Ok((feed, report))
+}
+
+fn read_entry(element: &Element, path: &str, report: &mut ConversionReport) -> Item {
+    let mut item = Item {
+        language: element.attr("xml:lang").map(|s| s.to_string()),
+        ..Item::default()
+    };
+    let mut id = None;
+    let mut updated = None;
+    let mut content = None;
+    let mut summary = None;
+    let mut authors = vec![];
+    let mut tags = vec![];
+    let mut attachments = vec![];
+    for child in &element.children {
+        let child_path = format!("{}.{}", path, child.qualified);
+        if child.namespace.as_ref().map(|s| &s[..]) != Some(ATOM_NS) {
+            report.unmapped(child_path);
+            continue;
+        }
+        match &child.name[..] {
+            "id" => id = child.trimmed(),
+            "title" => item.title = child.trimmed(),
+            "published" => item.date_published = read_date(child, &child_path, report),
+            "updated" => updated = read_date(child, &child_path, report),
+            "author" => authors.push(read_author(child, &child_path, report)),
+            "category" => tags.extend(child.attr("term").map(|s| s.to_string())),
+            "summary" => summary = child.trimmed().map(|text| (text_type(child), text)),
+            "content" => {
+                if child.attr("src").is_some() {
+                    report.lossy(child_path, "out-of-line content left out");
+                } else {
+                    if text_type(child) == "xhtml" {
+                        report.lossy(child_path, "xhtml content imported as its text");
+                    }
+                    content = Some((text_type(child), child.text.trim().to_string()));
+                }
+            },
+            "link" => {
+                let href = child.attr("href").map(|s| s.to_string());
+                match child.attr("rel").unwrap_or("alternate") {
+                    "alternate" if item.url.is_none() => item.url = href,
+                    "related" if item.external_url.is_none() => item.external_url = href,
+                    "enclosure" => match read_enclosure(child) {
+                        Some(attachment) => attachments.push(attachment),
+                        None => report.lossy(child_path, "the enclosure has no href, left out"),
+                    },
+                    rel => report.unmapped(format!("{}[rel={}]", child_path, rel)),
+                }
+            },
+            _ => report.unmapped(child_path),
+        }
+    }
+
+    // see the module documentation for how content and summary map
+    item.content = match (content, summary) {
+        (Some(("html", html)), Some((_, text))) => Content::Both(html, text),
+        (Some(("html", html)), None) => Content::Html(html),
+        (Some((_, text)), summary) => {
+            item.summary = summary.map(|(_, summary)| summary);
+            Content::Text(text)
+        },
+        (None, Some(("html", html))) => Content::Html(html),
+        (None, Some((_, text))) => Content::Text(text),
+        (None, None) => {
+            report.lossy(path, "the entry has no content or summary, content_text written empty");
+            Content::Text(String::new())
+        },
+    };
+
+    item.id = match id {
+        Some(id) => id,
+        None => match item.url {
+            Some(ref url) => {
+                report.lossy(format!("{}.id", path), "the entry has no id, its link is used as id");
+                url.clone()
+            },
+            None => {
+                report.lossy(format!("{}.id", path), "the entry has no id or link, a hash of it is used as id");
+                let text = |name| element.child(Some(ATOM_NS), name).map_or("", |e| &e.text[..]);
+                convert::derived_id(&[text("title"), text("content"), text("summary"),
+                                      text("published"), text("updated")])
+            },
+        },
+    };
+    if updated != item.date_published {
+        item.date_modified = updated;
+    }
+    if !authors.is_empty() {
+        item.authors = Some(authors);
+    }
+    if !tags.is_empty() {
+        item.tags = Some(tags);
+    }
+    if !attachments.is_empty() {
+        item.attachments = Some(attachments);
+    }
+    item
+}
+
+/// The `type` of a text construct, which defaults to `text`
+fn text_type(element: &Element) -> &'static str {
+    match element.attr("type") {
+        Some("html") => "html",
+        Some("xhtml") => "xhtml",
+        _ => "text",
+    }
+}
+
+fn read_date(element: &Element, path: &str, report: &mut ConversionReport) -> Option<String> {
+    let text = element.trimmed()?;
+    match date::check(&text) {
+        Ok(()) => Some(text.to_string()),
+        Err(e) => {
+            report.lossy(path, format!("invalid date '{}' left out: {}", text, e));
+            None
+        },
+    }
+}
+
+fn read_author(element: &Element, path: &str, report: &mut ConversionReport) -> Author {
+    let mut author = Author::builder().build();
+    for child in &element.children {
+        match (child.namespace.as_ref().map(|s| &s[..]), &child.name[..]) {
+            (Some(ATOM_NS), "name") => author.name = child.trimmed(),
+            (Some(ATOM_NS), "uri") => author.url = child.trimmed(),
+            _ => report.unmapped(format!("{}.{}", path, child.qualified)),
+        }
+    }
+    author
+}
+
+fn read_enclosure(element: &Element) -> Option<Attachment> {
+    let url = element.attr("href")?;
+    let mime_type = element.attr("type").unwrap_or("application/octet-stream");
+    let mut builder = Attachment::builder(url, mime_type);
+    if let Some(size) = element.attr("length").and_then(|s| s.trim().parse().ok()) {
+        builder = builder.size_in_bytes(size);
+    }
+    if let Some(title) = element.attr("title") {
+        builder = builder.title(title);
+    }
+    Some(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed() -> Feed {
+        ::from_str(r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "my feed",
+            "home_page_url": "https://example.com/",
+            "feed_url": "https://example.com/feed.json",
+            "icon": "https://example.com/logo.png",
+            "favicon": "https://example.com/favicon.ico",
+            "language": "en",
+            "authors": [{"name": "bob jones", "url": "https://example.com/bob"}],
+            "hubs": [{"type": "WebSub", "url": "https://example.com/hub"}],
+            "items": [
+                {
+                    "id": "1",
+                    "url": "https://example.com/1",
+                    "title": "both",
+                    "content_html": "<p>hello</p>",
+                    "content_text": "hello",
+                    "date_published": "2017-04-26T01:09:45+02:00",
+                    "date_modified": "2017-04-27T01:09:45+02:00",
+                    "tags": ["greetings"],
+                    "attachments": [{"url": "https://example.com/1.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 1024}]
+                },
+                {
+                    "id": "2",
+                    "title": "text and summary",
+                    "content_text": "two",
+                    "summary": "the second",
+                    "date_published": "2017-04-25T01:09:45+02:00"
+                },
+                {
+                    "id": "3",
+                    "title": "html",
+                    "content_html": "<p>three</p>",
+                    "date_published": "2017-04-24T01:09:45+02:00"
+                }
+            ]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn atom() {
+        let (atom, report) = to_string(&feed()).unwrap();
+        assert!(report.is_empty(), "{:?}", report);
+        assert!(atom.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">"#), "{}", atom);
+        assert!(atom.contains("<id>https://example.com/feed.json</id>"), "{}", atom);
+        assert!(atom.contains(r#"<link rel="self" href="https://example.com/feed.json" />"#), "{}", atom);
+        assert!(atom.contains("<updated>2017-04-27T01:09:45+02:00</updated>"), "{}", atom);
+        assert!(atom.contains(r#"<link rel="hub" href="https://example.com/hub" />"#), "{}", atom);
+        assert!(atom.contains("<icon>https://example.com/favicon.ico</icon>"), "{}", atom);
+        assert!(atom.contains("<logo>https://example.com/logo.png</logo>"), "{}", atom);
+        assert!(atom.contains("<published>2017-04-26T01:09:45+02:00</published>"), "{}", atom);
+        assert!(atom.contains(r#"<link rel="enclosure" href="https://example.com/1.mp3" type="audio/mpeg" length="1024" />"#), "{}", atom);
+        assert!(atom.contains("<summary>hello</summary>"), "{}", atom);
+        assert!(atom.contains(r#"<content type="html">&lt;p&gt;hello&lt;/p&gt;</content>"#), "{}", atom);
+        assert!(atom.contains(r#"<category term="greetings" />"#), "{}", atom);
+    }
+
+    #[test]
+    fn atom_round_trip() {
+        let original = feed();
+        let (atom, _) = to_string(&original).unwrap();
+        let (imported, report) = from_reader(atom.as_bytes()).unwrap();
+        assert_eq!(report.warnings.iter().map(|w| w.path()).collect::<Vec<_>>(),
+                   vec!["feed.updated"]);
+        assert_eq!(imported.title, original.title);
+        assert_eq!(imported.home_page_url, original.home_page_url);
+        assert_eq!(imported.feed_url, original.feed_url);
+        assert_eq!(imported.icon, original.icon);
+        assert_eq!(imported.favicon, original.favicon);
+        assert_eq!(imported.language, original.language);
+        assert_eq!(imported.authors, original.authors);
+        assert_eq!(imported.hubs, original.hubs);
+        assert_eq!(imported.items, original.items);
+    }
+
+    #[test]
+    fn atom_report() {
+        let mut feed = feed();
+        feed.items[1].content = Content::Both("<p>two</p>".to_string(), "two".to_string());
+        feed.items[2].title = None;
+        feed.items[2].date_published = None;
+        let (_, report) = to_string(&feed).unwrap();
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec!["items[1].content_text", "items[2].title", "items[2].updated"]);
+    }
+
+    #[test]
+    fn atom_import() {
+        let atom = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>my feed</title>
+  <id>urn:uuid:60a76c80-d399-11d9-b93C-0003939e0af6</id>
+  <rights>none</rights>
+  <entry>
+    <link href="https://example.com/1"/>
+    <title>one</title>
+    <updated>2017-04-26T01:09:45Z</updated>
+    <summary type="html">&lt;p&gt;one&lt;/p&gt;</summary>
+    <author><name>bob jones</name><email>bob@example.com</email></author>
+  </entry>
+  <entry>
+    <title>two</title>
+    <published>last week</published>
+  </entry>
+</feed>"#;
+        let (feed, report) = from_reader(atom.as_bytes()).unwrap();
+        assert_eq!(feed.title, "my feed");
+        let one = &feed.items[0];
+        assert_eq!(one.id, "https://example.com/1");
+        assert_eq!(one.content, Content::Html("<p>one</p>".to_string()));
+        assert_eq!(one.date_modified.as_ref().unwrap(), "2017-04-26T01:09:45Z");
+        assert_eq!(one.authors, Some(vec![Author::builder().name("bob jones").build()]));
+        assert_eq!(feed.items[1].id.len(), 16);
+
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec![
+            "feed.rights",
+            "feed.entry[0].author.email",
+            "feed.entry[0].id",
+            "feed.entry[1].published",
+            "feed.entry[1]",
+            "feed.entry[1].id",
+            "feed.id",
+        ]);
+        assert_eq!(feed.feed_url, None);
+        assert!(from_reader(r#"<rss version="2.0"/>"#.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn atom_import_feed_url() {
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>https://example.com/feed.xml</id></feed>"#;
+        let (feed, report) = from_reader(atom.as_bytes()).unwrap();
+        assert_eq!(feed.feed_url, Some("https://example.com/feed.xml".to_string()));
+        assert!(report.is_empty(), "{:?}", report);
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>https://example.com/</id>
+            <link href="https://example.com/"/><link rel="self" href="https://example.com/feed.xml"/></feed>"#;
+        let (feed, report) = from_reader(atom.as_bytes()).unwrap();
+        assert_eq!(feed.home_page_url, Some("https://example.com/".to_string()));
+        assert_eq!(feed.feed_url, Some("https://example.com/feed.xml".to_string()));
+        assert!(report.is_empty(), "{:?}", report);
+    }
+}
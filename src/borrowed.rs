@@ -0,0 +1,731 @@
+//! Feeds borrowing their strings from the JSON they were parsed from
+//!
+//! `FeedRef` and `ItemRef` mirror `Feed` and `Item`, but their strings are
+//! `Cow<'a, str>`s pointing into the input wherever possible. Only strings
+//! containing escape sequences need to be copied. Extension objects are
+//! still owned. `Feed` and `Item` are deserialized through these types
+//! too, so the two always accept the same documents.
+//!
+//! ```rust
+//! # extern crate jsonfeed;
+//! # use std::borrow::Cow;
+//! # fn main() {
+//! let json = r#"{
+//!     "version": "https://jsonfeed.org/version/1",
+//!     "title": "my feed",
+//!     "items": [{"id": "1", "content_text": "hello"}]
+//! }"#;
+//! let feed = jsonfeed::borrowed::from_str(json).unwrap();
+//! assert_eq!(feed.title, Cow::Borrowed("my feed"));
+//!
+//! let owned = feed.into_owned();
+//! assert_eq!(owned, jsonfeed::from_str(json).unwrap());
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, Visitor, MapAccess};
+use serde_json;
+use serde_path_to_error;
+
+use errors::*;
+use feed::{self, Feed, Author, Attachment, Hub};
+use item::{self, Item, Content};
+use extensions::{self, Extensions};
+use version::Version;
+
+/// Parses a feed, borrowing from `s`
+pub fn from_str<'a>(s: &'a str) -> Result<FeedRef<'a>> {
+    let feed: FeedRef = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(s))?;
+    check(&feed)?;
+    Ok(feed)
+}
+
+/// Parses a feed, borrowing from `v`
+pub fn from_slice<'a>(v: &'a [u8]) -> Result<FeedRef<'a>> {
+    let feed: FeedRef = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(v))?;
+    check(&feed)?;
+    Ok(feed)
+}
+
+fn check(feed: &FeedRef) -> Result<()> {
+    if !feed.version.is_known() {
+        return Err(Error::UnsupportedVersion { version: feed.version.to_string() });
+    }
+    Ok(())
+}
+
+/// A `Feed` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedRef<'a> {
+    pub version: Version,
+    pub title: Cow<'a, str>,
+    pub items: Vec<ItemRef<'a>>,
+    pub home_page_url: Option<Cow<'a, str>>,
+    pub feed_url: Option<Cow<'a, str>>,
+    pub description: Option<Cow<'a, str>>,
+    pub user_comment: Option<Cow<'a, str>>,
+    pub next_url: Option<Cow<'a, str>>,
+    pub icon: Option<Cow<'a, str>>,
+    pub favicon: Option<Cow<'a, str>>,
+    pub author: Option<AuthorRef<'a>>,
+    pub authors: Option<Vec<AuthorRef<'a>>>,
+    pub language: Option<Cow<'a, str>>,
+    pub expired: Option<bool>,
+    pub hubs: Option<Vec<HubRef<'a>>>,
+    pub extensions: Extensions,
+}
+
+impl<'a> FeedRef<'a> {
+    /// Copies every borrowed string, giving the equivalent `Feed`
+    pub fn into_owned(self) -> Feed {
+        Feed {
+            version: self.version,
+            title: self.title.into_owned(),
+            items: self.items.into_iter().map(ItemRef::into_owned).collect(),
+            home_page_url: owned(self.home_page_url),
+            feed_url: owned(self.feed_url),
+            description: owned(self.description),
+            user_comment: owned(self.user_comment),
+            next_url: owned(self.next_url),
+            icon: owned(self.icon),
+            favicon: owned(self.favicon),
+            author: self.author.map(AuthorRef::into_owned),
+            authors: self.authors.map(|authors| authors.into_iter().map(AuthorRef::into_owned).collect()),
+            language: owned(self.language),
+            expired: self.expired,
+            hubs: self.hubs.map(|hubs| hubs.into_iter().map(HubRef::into_owned).collect()),
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// An `Item` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemRef<'a> {
+    pub id: Cow<'a, str>,
+    pub url: Option<Cow<'a, str>>,
+    pub external_url: Option<Cow<'a, str>>,
+    pub title: Option<Cow<'a, str>>,
+    pub content: ContentRef<'a>,
+    pub summary: Option<Cow<'a, str>>,
+    pub image: Option<Cow<'a, str>>,
+    pub banner_image: Option<Cow<'a, str>>,
+    pub date_published: Option<Cow<'a, str>>,
+    pub date_modified: Option<Cow<'a, str>>,
+    pub author: Option<AuthorRef<'a>>,
+    pub authors: Option<Vec<AuthorRef<'a>>>,
+    pub tags: Option<Vec<Cow<'a, str>>>,
+    pub language: Option<Cow<'a, str>>,
+    pub attachments: Option<Vec<AttachmentRef<'a>>>,
+    pub extensions: Extensions,
+}
+
+impl<'a> ItemRef<'a> {
+    /// Copies every borrowed string, giving the equivalent `Item`
+    pub fn into_owned(self) -> Item {
+        Item {
+            id: self.id.into_owned(),
+            url: owned(self.url),
+            external_url: owned(self.external_url),
+            title: owned(self.title),
+            content: self.content.into_owned(),
+            summary: owned(self.summary),
+            image: owned(self.image),
+            banner_image: owned(self.banner_image),
+            date_published: owned(self.date_published),
+            date_modified: owned(self.date_modified),
+            author: self.author.map(AuthorRef::into_owned),
+            authors: self.authors.map(|authors| authors.into_iter().map(AuthorRef::into_owned).collect()),
+            tags: self.tags.map(|tags| tags.into_iter().map(Cow::into_owned).collect()),
+            language: owned(self.language),
+            attachments: self.attachments.map(|attachments| {
+                attachments.into_iter().map(AttachmentRef::into_owned).collect()
+            }),
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// A `Content` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentRef<'a> {
+    Html(Cow<'a, str>),
+    Text(Cow<'a, str>),
+    Both(Cow<'a, str>, Cow<'a, str>),
+}
+
+impl<'a> ContentRef<'a> {
+    /// The `content_html` value
+    pub fn html(&self) -> Option<&str> {
+        match *self {
+            ContentRef::Html(ref s) | ContentRef::Both(ref s, _) => Some(s),
+            ContentRef::Text(_) => None,
+        }
+    }
+
+    /// The `content_text` value
+    pub fn text(&self) -> Option<&str> {
+        match *self {
+            ContentRef::Text(ref t) | ContentRef::Both(_, ref t) => Some(t),
+            ContentRef::Html(_) => None,
+        }
+    }
+
+    pub fn into_owned(self) -> Content {
+        match self {
+            ContentRef::Html(s) => Content::Html(s.into_owned()),
+            ContentRef::Text(t) => Content::Text(t.into_owned()),
+            ContentRef::Both(s, t) => Content::Both(s.into_owned(), t.into_owned()),
+        }
+    }
+}
+
+/// An `Author` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorRef<'a> {
+    pub name: Option<Cow<'a, str>>,
+    pub url: Option<Cow<'a, str>>,
+    pub avatar: Option<Cow<'a, str>>,
+    pub extensions: Extensions,
+}
+
+impl<'a> AuthorRef<'a> {
+    pub fn into_owned(self) -> Author {
+        Author {
+            name: owned(self.name),
+            url: owned(self.url),
+            avatar: owned(self.avatar),
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// An `Attachment` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentRef<'a> {
+    pub url: Cow<'a, str>,
+    pub mime_type: Cow<'a, str>,
+    pub title: Option<Cow<'a, str>>,
+    pub size_in_bytes: Option<u64>,
+    pub duration_in_seconds: Option<u64>,
+    pub extensions: Extensions,
+}
+
+impl<'a> AttachmentRef<'a> {
+    pub fn into_owned(self) -> Attachment {
+        Attachment {
+            url: self.url.into_owned(),
+            mime_type: self.mime_type.into_owned(),
+            title: owned(self.title),
+            size_in_bytes: self.size_in_bytes,
+            duration_in_seconds: self.duration_in_seconds,
+            extensions: self.extensions,
+        }
+    }
+}
+
+/// A `Hub` borrowing its strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct HubRef<'a> {
+    pub hub_type: Cow<'a, str>,
+    pub url: Cow<'a, str>,
+    pub extensions: Extensions,
+}
+
+impl<'a> HubRef<'a> {
+    pub fn into_owned(self) -> Hub {
+        let mut hub = Hub::new(self.hub_type.into_owned(), self.url.into_owned());
+        hub.extensions = self.extensions;
+        hub
+    }
+}
+
+fn owned(s: Option<Cow<str>>) -> Option<String> {
+    s.map(Cow::into_owned)
+}
+
+/// A string that is borrowed when it has no escape sequences
+///
+/// `Cow<str>` always deserializes to an owned string unless serde is told
+/// otherwise, which it can't be when the `Cow` is nested in an `Option`
+/// or `Vec`.
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+/// Reads the next value of a map, failing if the key was already seen
+fn next_value<'de, T, V>(map: &mut V, seen: bool, key: &'static str) -> ::std::result::Result<T, V::Error>
+    where T: Deserialize<'de>,
+          V: MapAccess<'de>
+{
+    if seen {
+        return Err(duplicate_field_error(key));
+    }
+    map.next_value()
+}
+
+fn next_string<'de, V>(map: &mut V, seen: bool, key: &'static str)
+        -> ::std::result::Result<Option<Cow<'de, str>>, V::Error>
+    where V: MapAccess<'de>
+{
+    Ok(next_value::<Option<Borrowed>, V>(map, seen, key)?.map(|s| s.0))
+}
+
+fn required<T, E: de::Error>(value: Option<T>, key: &'static str) -> ::std::result::Result<T, E> {
+    value.ok_or_else(|| missing_field_error(key))
+}
+
+/// Walks the entries of an object, handing each key to `field`, which
+/// reads the value and returns true if it knows the key
+///
+/// Extension keys `field` doesn't know are collected and returned, and
+/// other keys are ignored. When `fields` is given, any other key is an
+/// error.
+fn visit_fields<'de, V, F>(map: &mut V, fields: Option<&'static [&'static str]>, mut field: F)
+        -> ::std::result::Result<Extensions, V::Error>
+    where V: MapAccess<'de>,
+          F: FnMut(&str, &mut V) -> ::std::result::Result<bool, V::Error>
+{
+    let mut extensions = Extensions::new();
+    while let Some(Key(key)) = map.next_key_seed(KeySeed { fields })? {
+        if field(&key, map)? {
+            continue;
+        }
+        if extensions::is_extension_key(&key) {
+            if extensions.contains_key(&key[..]) {
+                return Err(duplicate_field_error(&key));
+            }
+            let value = map.next_value()?;
+            extensions.insert(key.into_owned(), value);
+        } else {
+            map.next_value::<de::IgnoredAny>()?;
+        }
+    }
+    Ok(extensions)
+}
+
+/// A key of an object, looked up without copying
+struct Key<'a>(Cow<'a, str>);
+
+/// Reads a `Key`, rejecting any key that is neither in `fields` nor an
+/// extension when `fields` is given
+struct KeySeed {
+    fields: Option<&'static [&'static str]>,
+}
+
+impl KeySeed {
+    fn check<E: de::Error>(&self, key: &str) -> ::std::result::Result<(), E> {
+        match self.fields {
+            Some(fields) if !fields.contains(&key) && !extensions::is_extension_key(key) => {
+                Err(de::Error::unknown_field(key, fields))
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Key<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> ::std::result::Result<Key<'de>, D::Error>
+        where D: Deserializer<'de>
+    {
+        // identifiers rather than strings, so error paths can name keys
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Key<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> ::std::result::Result<Key<'de>, E>
+        where E: de::Error
+    {
+        self.check(value)?;
+        Ok(Key(Cow::Borrowed(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> ::std::result::Result<Key<'de>, E>
+        where E: de::Error
+    {
+        self.check(value)?;
+        Ok(Key(Cow::Owned(value.to_string())))
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for FeedRef<'a> {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct FeedVisitor;
+
+        impl<'de> Visitor<'de> for FeedVisitor {
+            type Value = FeedRef<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a feed")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> ::std::result::Result<FeedRef<'de>, V::Error>
+                where V: MapAccess<'de>
+            {
+                let mut version: Option<Version> = None;
+                let mut title: Option<Borrowed> = None;
+                let mut items = None;
+                let mut home_page_url = None;
+                let mut feed_url = None;
+                let mut description = None;
+                let mut user_comment = None;
+                let mut next_url = None;
+                let mut icon = None;
+                let mut favicon = None;
+                let mut author = None;
+                let mut authors = None;
+                let mut language = None;
+                let mut expired = None;
+                let mut hubs = None;
+
+                let extensions = visit_fields(&mut map, None, |key, map| {
+                    match key {
+                        "version" => version = Some(next_value(map, version.is_some(), "version")?),
+                        "title" => title = Some(next_value(map, title.is_some(), "title")?),
+                        "items" => items = Some(next_value(map, items.is_some(), "items")?),
+                        "home_page_url" => home_page_url = next_string(map, home_page_url.is_some(), "home_page_url")?,
+                        "feed_url" => feed_url = next_string(map, feed_url.is_some(), "feed_url")?,
+                        "description" => description = next_string(map, description.is_some(), "description")?,
+                        "user_comment" => user_comment = next_string(map, user_comment.is_some(), "user_comment")?,
+                        "next_url" => next_url = next_string(map, next_url.is_some(), "next_url")?,
+                        "icon" => icon = next_string(map, icon.is_some(), "icon")?,
+                        "favicon" => favicon = next_string(map, favicon.is_some(), "favicon")?,
+                        "author" => author = next_value(map, author.is_some(), "author")?,
+                        "authors" => authors = next_value(map, authors.is_some(), "authors")?,
+                        "language" => language = next_string(map, language.is_some(), "language")?,
+                        "expired" => expired = next_value(map, expired.is_some(), "expired")?,
+                        "hubs" => hubs = next_value(map, hubs.is_some(), "hubs")?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                Ok(FeedRef {
+                    version: required(version, "version")?,
+                    title: required(title, "title")?.0,
+                    items: required(items, "items")?,
+                    home_page_url,
+                    feed_url,
+                    description,
+                    user_comment,
+                    next_url,
+                    icon,
+                    favicon,
+                    author,
+                    authors,
+                    language,
+                    expired,
+                    hubs,
+                    extensions,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Feed", feed::FIELDS, FeedVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for ItemRef<'a> {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct ItemVisitor;
+
+        impl<'de> Visitor<'de> for ItemVisitor {
+            type Value = ItemRef<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an item")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> ::std::result::Result<ItemRef<'de>, V::Error>
+                where V: MapAccess<'de>
+            {
+                let mut id: Option<Borrowed> = None;
+                let mut url = None;
+                let mut external_url = None;
+                let mut title = None;
+                let mut content_html = None;
+                let mut content_text = None;
+                let mut summary = None;
+                let mut image = None;
+                let mut banner_image = None;
+                let mut date_published = None;
+                let mut date_modified = None;
+                let mut author = None;
+                let mut authors = None;
+                let mut tags: Option<Vec<Borrowed>> = None;
+                let mut language = None;
+                let mut attachments = None;
+
+                let extensions = visit_fields(&mut map, Some(item::FIELDS), |key, map| {
+                    match key {
+                        "id" => id = Some(next_value(map, id.is_some(), "id")?),
+                        "url" => url = next_string(map, url.is_some(), "url")?,
+                        "external_url" => external_url = next_string(map, external_url.is_some(), "external_url")?,
+                        "title" => title = next_string(map, title.is_some(), "title")?,
+                        "content_html" => content_html = next_string(map, content_html.is_some(), "content_html")?,
+                        "content_text" => content_text = next_string(map, content_text.is_some(), "content_text")?,
+                        "summary" => summary = next_string(map, summary.is_some(), "summary")?,
+                        "image" => image = next_string(map, image.is_some(), "image")?,
+                        "banner_image" => banner_image = next_string(map, banner_image.is_some(), "banner_image")?,
+                        "date_published" => {
+                            date_published = next_string(map, date_published.is_some(), "date_published")?
+                        },
+                        "date_modified" => {
+                            date_modified = next_string(map, date_modified.is_some(), "date_modified")?
+                        },
+                        "author" => author = next_value(map, author.is_some(), "author")?,
+                        "authors" => authors = next_value(map, authors.is_some(), "authors")?,
+                        "tags" => tags = next_value(map, tags.is_some(), "tags")?,
+                        "language" => language = next_string(map, language.is_some(), "language")?,
+                        "attachments" => attachments = next_value(map, attachments.is_some(), "attachments")?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                let id = required(id, "id")?.0;
+                let content = match (content_html, content_text) {
+                    (Some(s), Some(t)) => ContentRef::Both(s, t),
+                    (Some(s), None) => ContentRef::Html(s),
+                    (None, Some(t)) => ContentRef::Text(t),
+                    (None, None) => return Err(missing_field_error("content_html or content_text")),
+                };
+
+                Ok(ItemRef {
+                    id,
+                    url,
+                    external_url,
+                    title,
+                    content,
+                    summary,
+                    image,
+                    banner_image,
+                    date_published,
+                    date_modified,
+                    author,
+                    authors,
+                    tags: tags.map(|tags| tags.into_iter().map(|s| s.0).collect()),
+                    language,
+                    attachments,
+                    extensions,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Item", item::FIELDS, ItemVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for AuthorRef<'a> {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct AuthorVisitor;
+
+        impl<'de> Visitor<'de> for AuthorVisitor {
+            type Value = AuthorRef<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an author")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> ::std::result::Result<AuthorRef<'de>, V::Error>
+                where V: MapAccess<'de>
+            {
+                let mut name = None;
+                let mut url = None;
+                let mut avatar = None;
+
+                let extensions = visit_fields(&mut map, None, |key, map| {
+                    match key {
+                        "name" => name = next_string(map, name.is_some(), "name")?,
+                        "url" => url = next_string(map, url.is_some(), "url")?,
+                        "avatar" => avatar = next_string(map, avatar.is_some(), "avatar")?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                Ok(AuthorRef { name, url, avatar, extensions })
+            }
+        }
+
+        deserializer.deserialize_struct("Author", &["name", "url", "avatar"], AuthorVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for AttachmentRef<'a> {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct AttachmentVisitor;
+
+        impl<'de> Visitor<'de> for AttachmentVisitor {
+            type Value = AttachmentRef<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an attachment")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> ::std::result::Result<AttachmentRef<'de>, V::Error>
+                where V: MapAccess<'de>
+            {
+                let mut url: Option<Borrowed> = None;
+                let mut mime_type: Option<Borrowed> = None;
+                let mut title = None;
+                let mut size_in_bytes = None;
+                let mut duration_in_seconds = None;
+
+                let extensions = visit_fields(&mut map, None, |key, map| {
+                    match key {
+                        "url" => url = Some(next_value(map, url.is_some(), "url")?),
+                        "mime_type" => mime_type = Some(next_value(map, mime_type.is_some(), "mime_type")?),
+                        "title" => title = next_string(map, title.is_some(), "title")?,
+                        "size_in_bytes" => {
+                            size_in_bytes = next_value(map, size_in_bytes.is_some(), "size_in_bytes")?
+                        },
+                        "duration_in_seconds" => {
+                            duration_in_seconds = next_value(map, duration_in_seconds.is_some(), "duration_in_seconds")?
+                        },
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                Ok(AttachmentRef {
+                    url: required(url, "url")?.0,
+                    mime_type: required(mime_type, "mime_type")?.0,
+                    title,
+                    size_in_bytes,
+                    duration_in_seconds,
+                    extensions,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Attachment", &["url", "mime_type", "title", "size_in_bytes",
+                                                        "duration_in_seconds"], AttachmentVisitor)
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for HubRef<'a> {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        struct HubVisitor;
+
+        impl<'de> Visitor<'de> for HubVisitor {
+            type Value = HubRef<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a hub")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> ::std::result::Result<HubRef<'de>, V::Error>
+                where V: MapAccess<'de>
+            {
+                let mut hub_type: Option<Borrowed> = None;
+                let mut url: Option<Borrowed> = None;
+
+                let extensions = visit_fields(&mut map, None, |key, map| {
+                    match key {
+                        "type" => hub_type = Some(next_value(map, hub_type.is_some(), "type")?),
+                        "url" => url = Some(next_value(map, url.is_some(), "url")?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                Ok(HubRef {
+                    hub_type: required(hub_type, "type")?.0,
+                    url: required(url, "url")?.0,
+                    extensions,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Hub", &["type", "url"], HubVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "my \"feed\"",
+        "home_page_url": "https://example.com/",
+        "authors": [{"name": "bob", "url": "https://example.com/bob", "_x": 1}],
+        "hubs": [{"type": "WebSub", "url": "https://example.com/hub", "_lease": 3600}],
+        "_ext": {"a": [1, 2]},
+        "unknown": true,
+        "items": [
+            {"id": "1", "content_text": "one", "tags": ["a", "b"],
+             "date_published": "2017-05-17T10:02:12-04:00",
+             "attachments": [{"url": "https://example.com/a.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 10}]},
+            {"id": "2", "content_html": "<p>two</p>", "content_text": "two", "_ourapp": {"x": "y"}}
+        ]
+    }"#;
+
+    #[test]
+    fn borrows_strings() {
+        let feed = from_str(JSON).unwrap();
+        assert!(matches!(feed.home_page_url, Some(Cow::Borrowed(_))));
+        assert!(matches!(feed.authors.as_ref().unwrap()[0].name, Some(Cow::Borrowed(_))));
+        assert!(matches!(feed.hubs.as_ref().unwrap()[0].url, Cow::Borrowed(_)));
+        let item = &feed.items[0];
+        assert!(matches!(item.id, Cow::Borrowed(_)));
+        assert!(matches!(item.tags.as_ref().unwrap()[1], Cow::Borrowed(_)));
+        assert!(matches!(item.attachments.as_ref().unwrap()[0].url, Cow::Borrowed(_)));
+        assert!(matches!(item.content, ContentRef::Text(Cow::Borrowed("one"))));
+
+        // escaped strings can't be borrowed
+        assert!(matches!(feed.title, Cow::Owned(ref title) if title == "my \"feed\""));
+    }
+
+    #[test]
+    fn into_owned() {
+        assert_eq!(from_slice(JSON.as_bytes()).unwrap().into_owned(), ::from_str(JSON).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        let unknown_version = r#"{"version": "https://jsonfeed.org/version/2", "title": "", "items": []}"#;
+        match from_str(unknown_version) {
+            Err(Error::UnsupportedVersion { .. }) => {},
+            other => panic!("expected an unsupported version error, got {:?}", other),
+        }
+
+        let no_content = r#"{"version": "https://jsonfeed.org/version/1", "title": "", "items": [{"id": "1"}]}"#;
+        match from_str(no_content) {
+            Err(Error::MissingField { ref location, .. }) => {
+                assert_eq!(location.path, "items[0].content_html or content_text")
+            },
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+
+        let unknown_field = r#"{"version": "https://jsonfeed.org/version/1", "title": "", "items": [
+            {"id": "1", "content_text": "", "bogus": 1}
+        ]}"#;
+        assert_eq!(
+            from_str(unknown_field).unwrap_err().to_string(),
+            ::from_str(unknown_field).unwrap_err().to_string()
+        );
+    }
+}
@@ -1,8 +1,14 @@
 use std::default::Default;
+use std::marker::PhantomData;
+
+use serde_json::Value;
 
 use errors::*;
-use feed::{Feed, Author, Attachment};
+use feed::{Feed, Author, Attachment, Hub};
 use item::{Content, Item};
+use extensions::{self, Extensions};
+use version::Version;
+use date::{self, IntoDate};
 
 /// Feed Builder
 ///
@@ -10,23 +16,124 @@ use item::{Content, Item};
 /// which can be serialized later into a JSON string
 pub struct Builder(Feed);
 
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
 impl Builder {
     pub fn new() -> Builder {
         Builder(Feed::default())
     }
 
+    pub fn version<V: Into<Version>>(mut self, v: V) -> Builder {
+        self.0.version = v.into();
+        self
+    }
+
     pub fn title<I: Into<String>>(mut self, t: I) -> Builder {
         self.0.title = t.into();
         self
     }
 
+    pub fn home_page_url<I: Into<String>>(mut self, url: I) -> Builder {
+        self.0.home_page_url = Some(url.into());
+        self
+    }
+
+    pub fn feed_url<I: Into<String>>(mut self, url: I) -> Builder {
+        self.0.feed_url = Some(url.into());
+        self
+    }
+
+    pub fn description<I: Into<String>>(mut self, d: I) -> Builder {
+        self.0.description = Some(d.into());
+        self
+    }
+
+    pub fn user_comment<I: Into<String>>(mut self, c: I) -> Builder {
+        self.0.user_comment = Some(c.into());
+        self
+    }
+
+    pub fn next_url<I: Into<String>>(mut self, url: I) -> Builder {
+        self.0.next_url = Some(url.into());
+        self
+    }
+
+    pub fn icon<I: Into<String>>(mut self, url: I) -> Builder {
+        self.0.icon = Some(url.into());
+        self
+    }
+
+    pub fn favicon<I: Into<String>>(mut self, url: I) -> Builder {
+        self.0.favicon = Some(url.into());
+        self
+    }
+
+    /// Sets the deprecated, JSON Feed 1.0 `author` field
+    pub fn author(mut self, author: Author) -> Builder {
+        self.0.author = Some(author);
+        self
+    }
+
+    /// Adds to the JSON Feed 1.1 `authors` field
+    pub fn authors<I: IntoIterator<Item = Author>>(mut self, authors: I) -> Builder {
+        self.0.authors.get_or_insert_with(Vec::new).extend(authors);
+        self
+    }
+
+    pub fn language<I: Into<String>>(mut self, language: I) -> Builder {
+        self.0.language = Some(language.into());
+        self
+    }
+
+    pub fn expired(mut self, expired: bool) -> Builder {
+        self.0.expired = Some(expired);
+        self
+    }
+
+    pub fn hub<T: Into<String>, U: Into<String>>(mut self, type_: T, url: U) -> Builder {
+        self.0.hubs.get_or_insert_with(Vec::new).push(Hub::new(type_, url));
+        self
+    }
+
+    pub fn hubs<I: IntoIterator<Item = Hub>>(mut self, hubs: I) -> Builder {
+        self.0.hubs.get_or_insert_with(Vec::new).extend(hubs);
+        self
+    }
+
     pub fn item(mut self, item: Item) -> Builder {
         self.0.items.push(item);
         self
     }
 
-    pub fn build(self) -> Feed {
-        self.0
+    pub fn items<I: IntoIterator<Item = Item>>(mut self, items: I) -> Builder {
+        self.0.items.extend(items);
+        self
+    }
+
+    /// Adds a custom extension object. The key must start with an underscore.
+    pub fn extension<K: Into<String>>(mut self, key: K, value: Value) -> Builder {
+        self.0.extensions.insert(key.into(), value);
+        self
+    }
+
+    /// Builds the feed, checking that the title is non-empty, the version
+    /// is supported and extension keys start with an underscore
+    pub fn build(self) -> Result<Feed> {
+        let feed = self.0;
+        if feed.title.trim().is_empty() {
+            return Err(Error::missing_field("title"));
+        }
+        if !feed.version.is_known() {
+            return Err(Error::UnsupportedVersion { version: feed.version.to_string() });
+        }
+        if let Some(key) = feed.extensions.keys().find(|k| !extensions::is_extension_key(k)) {
+            return Err(Error::invalid_value("extension keys must start with an underscore", &key[..]));
+        }
+        Ok(feed)
     }
 }
 
@@ -44,8 +151,19 @@ pub struct ItemBuilder {
     pub date_published: Option<String>,
     pub date_modified: Option<String>,
     pub author: Option<Author>,
+    pub authors: Option<Vec<Author>>,
     pub tags: Option<Vec<String>>,
+    pub language: Option<String>,
     pub attachments: Option<Vec<Attachment>>,
+    pub extensions: Extensions,
+    /// Use `url` as the id when no id is set
+    pub id_from_url: bool,
+}
+
+impl Default for ItemBuilder {
+    fn default() -> ItemBuilder {
+        ItemBuilder::new()
+    }
 }
 
 impl ItemBuilder {
@@ -62,44 +180,140 @@ impl ItemBuilder {
             date_published: None,
             date_modified: None,
             author: None,
+            authors: None,
             tags: None,
+            language: None,
             attachments: None,
+            extensions: Extensions::new(),
+            id_from_url: false,
         }
     }
 
+    pub fn id<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.id = Some(i.into());
+        self
+    }
+
+    /// Use the item's `url` as its id, unless an id is set explicitly
+    pub fn id_from_url(mut self) -> ItemBuilder {
+        self.id_from_url = true;
+        self
+    }
+
+    pub fn url<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.url = Some(i.into());
+        self
+    }
+
+    pub fn external_url<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.external_url = Some(i.into());
+        self
+    }
+
     pub fn title<I: Into<String>>(mut self, i: I) -> ItemBuilder {
         self.title = Some(i.into());
         self
     }
 
     pub fn content_html<I: Into<String>>(mut self, i: I) -> ItemBuilder {
-        match self.content {
-            Some(Content::Text(t)) => {
-                self.content = Some(Content::Both(i.into(), t));
-            },
-            _ => {
-                self.content = Some(Content::Html(i.into()));
-            }
-        }
+        self.content = Some(match self.content.take() {
+            Some(content) => content.with_html(i),
+            None => Content::Html(i.into()),
+        });
         self
     }
 
     pub fn content_text<I: Into<String>>(mut self, i: I) -> ItemBuilder {
-        match self.content {
-            Some(Content::Html(s)) => {
-                self.content = Some(Content::Both(s, i.into()));
-            },
-            _ => {
-                self.content = Some(Content::Text(i.into()));
-            },
-        }
+        self.content = Some(match self.content.take() {
+            Some(content) => content.with_text(i),
+            None => Content::Text(i.into()),
+        });
+        self
+    }
+
+    pub fn summary<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.summary = Some(i.into());
+        self
+    }
+
+    pub fn image<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.image = Some(i.into());
+        self
+    }
+
+    pub fn banner_image<I: Into<String>>(mut self, i: I) -> ItemBuilder {
+        self.banner_image = Some(i.into());
+        self
+    }
+
+    /// Sets `date_published`. `build()` fails unless it is an RFC 3339 timestamp.
+    pub fn date_published<D: IntoDate>(mut self, d: D) -> ItemBuilder {
+        self.date_published = Some(d.into_date());
+        self
+    }
+
+    /// Sets `date_modified`. `build()` fails unless it is an RFC 3339 timestamp.
+    pub fn date_modified<D: IntoDate>(mut self, d: D) -> ItemBuilder {
+        self.date_modified = Some(d.into_date());
+        self
+    }
+
+    /// Sets the deprecated, JSON Feed 1.0 `author` field
+    pub fn author(mut self, author: Author) -> ItemBuilder {
+        self.author = Some(author);
+        self
+    }
+
+    /// Adds to the JSON Feed 1.1 `authors` field
+    pub fn authors<I: IntoIterator<Item = Author>>(mut self, authors: I) -> ItemBuilder {
+        self.authors.get_or_insert_with(Vec::new).extend(authors);
         self
     }
 
-    pub fn build(self) -> Result<Item> {
-        if self.id.is_none() || self.content.is_none() {
-            return Err("missing field 'id' or 'content_*'".into());
+    pub fn tag<I: Into<String>>(mut self, tag: I) -> ItemBuilder {
+        self.tags.get_or_insert_with(Vec::new).push(tag.into());
+        self
+    }
+
+    pub fn tags<I, T>(mut self, tags: I) -> ItemBuilder
+        where I: IntoIterator<Item = T>,
+              T: Into<String>
+    {
+        self.tags.get_or_insert_with(Vec::new).extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn language<I: Into<String>>(mut self, language: I) -> ItemBuilder {
+        self.language = Some(language.into());
+        self
+    }
+
+    pub fn attachment(mut self, attachment: Attachment) -> ItemBuilder {
+        self.attachments.get_or_insert_with(Vec::new).push(attachment);
+        self
+    }
+
+    /// Adds a custom extension object. The key must start with an underscore.
+    pub fn extension<K: Into<String>>(mut self, key: K, value: Value) -> ItemBuilder {
+        self.extensions.insert(key.into(), value);
+        self
+    }
+
+    pub fn build(mut self) -> Result<Item> {
+        if self.id.is_none() && self.id_from_url {
+            self.id = self.url.clone();
+        }
+        if let Some(key) = self.extensions.keys().find(|k| !extensions::is_extension_key(k)) {
+            return Err(Error::invalid_value("extension keys must start with an underscore", &key[..]));
+        }
+        if self.id.is_none() {
+            return Err(Error::missing_field("id"));
+        }
+        if self.content.is_none() {
+            return Err(Error::missing_field("content_html or content_text"));
         }
+        check_date(&self.date_published, "date_published")?;
+        check_date(&self.date_modified, "date_modified")?;
         Ok(Item {
             id: self.id.unwrap(),
             url: self.url,
@@ -112,9 +326,395 @@ impl ItemBuilder {
             date_published: self.date_published,
             date_modified: self.date_modified,
             author: self.author,
+            authors: self.authors,
             tags: self.tags,
-            attachments: self.attachments
+            language: self.language,
+            attachments: self.attachments,
+            extensions: self.extensions,
         })
     }
 }
 
+
+
+fn check_date<S: AsRef<str>>(d: &Option<S>, field: &'static str) -> Result<()> {
+    match *d {
+        Some(ref d) => date::check(d.as_ref()).map_err(|message| Error::invalid_value(message, field)),
+        None => Ok(()),
+    }
+}
+
+/// Marks a `TypedItemBuilder` that has no id yet
+pub struct NoId;
+/// Marks a `TypedItemBuilder` that has an id
+pub struct HasId;
+/// Marks a `TypedItemBuilder` that has no content yet
+pub struct NoContent;
+/// Marks a `TypedItemBuilder` that has `content_html` and/or `content_text`
+pub struct HasContent;
+
+/// Builder object for an item, which checks at compile time that the
+/// item has an id and content
+///
+/// `build()` can only be called once both `id()` and one of the
+/// `content_*()` methods have been called, so unlike `ItemBuilder::build`
+/// it can't fail.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::Item;
+/// # fn main() {
+/// let item = Item::typed_builder()
+///                 .content_text("Hello, World!")
+///                 .id("1")
+///                 .build();
+/// assert_eq!(item.id, "1");
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # extern crate jsonfeed;
+/// # use jsonfeed::Item;
+/// # fn main() {
+/// let item = Item::typed_builder()
+///                 .content_text("Hello, World!")
+///                 .build();
+/// # }
+/// ```
+pub struct TypedItemBuilder<I, C> {
+    inner: ItemBuilder,
+    state: PhantomData<(I, C)>,
+}
+
+impl TypedItemBuilder<NoId, NoContent> {
+    pub fn new() -> TypedItemBuilder<NoId, NoContent> {
+        TypedItemBuilder {
+            inner: ItemBuilder::new(),
+            state: PhantomData,
+        }
+    }
+}
+
+impl Default for TypedItemBuilder<NoId, NoContent> {
+    fn default() -> TypedItemBuilder<NoId, NoContent> {
+        TypedItemBuilder::new()
+    }
+}
+
+impl<I, C> TypedItemBuilder<I, C> {
+    fn with<F: FnOnce(ItemBuilder) -> ItemBuilder, J, D>(self, f: F) -> TypedItemBuilder<J, D> {
+        TypedItemBuilder {
+            inner: f(self.inner),
+            state: PhantomData,
+        }
+    }
+
+    pub fn id<S: Into<String>>(self, i: S) -> TypedItemBuilder<HasId, C> {
+        self.with(|b| b.id(i))
+    }
+
+    pub fn content_html<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, HasContent> {
+        self.with(|b| b.content_html(i))
+    }
+
+    pub fn content_text<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, HasContent> {
+        self.with(|b| b.content_text(i))
+    }
+
+    pub fn url<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.url(i))
+    }
+
+    pub fn external_url<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.external_url(i))
+    }
+
+    pub fn title<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.title(i))
+    }
+
+    pub fn summary<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.summary(i))
+    }
+
+    pub fn image<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.image(i))
+    }
+
+    pub fn banner_image<S: Into<String>>(self, i: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.banner_image(i))
+    }
+
+    /// Sets `date_published`, which must be an RFC 3339 timestamp
+    pub fn date_published<D: IntoDate>(self, d: D) -> Result<TypedItemBuilder<I, C>> {
+        let d = d.into_date();
+        check_date(&Some(&d), "date_published")?;
+        Ok(self.with(|b| b.date_published(d)))
+    }
+
+    /// Sets `date_modified`, which must be an RFC 3339 timestamp
+    pub fn date_modified<D: IntoDate>(self, d: D) -> Result<TypedItemBuilder<I, C>> {
+        let d = d.into_date();
+        check_date(&Some(&d), "date_modified")?;
+        Ok(self.with(|b| b.date_modified(d)))
+    }
+
+    /// Sets the deprecated, JSON Feed 1.0 `author` field
+    pub fn author(self, author: Author) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.author(author))
+    }
+
+    /// Adds to the JSON Feed 1.1 `authors` field
+    pub fn authors<A: IntoIterator<Item = Author>>(self, authors: A) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.authors(authors))
+    }
+
+    pub fn tag<S: Into<String>>(self, tag: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.tag(tag))
+    }
+
+    pub fn tags<T, S>(self, tags: T) -> TypedItemBuilder<I, C>
+        where T: IntoIterator<Item = S>,
+              S: Into<String>
+    {
+        self.with(|b| b.tags(tags))
+    }
+
+    pub fn language<S: Into<String>>(self, language: S) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.language(language))
+    }
+
+    pub fn attachment(self, attachment: Attachment) -> TypedItemBuilder<I, C> {
+        self.with(|b| b.attachment(attachment))
+    }
+}
+
+impl TypedItemBuilder<HasId, HasContent> {
+    pub fn build(self) -> Item {
+        self.inner.build().expect("an id and content have been set")
+    }
+}
+
+/// Builder object for an attachment of an item
+pub struct AttachmentBuilder(Attachment);
+
+impl AttachmentBuilder {
+    pub fn new<U: Into<String>, M: Into<String>>(url: U, mime_type: M) -> AttachmentBuilder {
+        AttachmentBuilder(Attachment {
+            url: url.into(),
+            mime_type: mime_type.into(),
+            title: None,
+            size_in_bytes: None,
+            duration_in_seconds: None,
+            extensions: Extensions::new(),
+        })
+    }
+
+    pub fn title<I: Into<String>>(mut self, t: I) -> AttachmentBuilder {
+        self.0.title = Some(t.into());
+        self
+    }
+
+    pub fn size_in_bytes(mut self, size: u64) -> AttachmentBuilder {
+        self.0.size_in_bytes = Some(size);
+        self
+    }
+
+    pub fn duration_in_seconds(mut self, duration: u64) -> AttachmentBuilder {
+        self.0.duration_in_seconds = Some(duration);
+        self
+    }
+
+    pub fn build(self) -> Attachment {
+        self.0
+    }
+}
+
+/// Builder object for an author of a feed or item
+pub struct AuthorBuilder(Author);
+
+impl Default for AuthorBuilder {
+    fn default() -> AuthorBuilder {
+        AuthorBuilder::new()
+    }
+}
+
+impl AuthorBuilder {
+    pub fn new() -> AuthorBuilder {
+        AuthorBuilder(Author {
+            name: None,
+            url: None,
+            avatar: None,
+            extensions: Extensions::new(),
+        })
+    }
+
+    pub fn name<I: Into<String>>(mut self, name: I) -> AuthorBuilder {
+        self.0.name = Some(name.into());
+        self
+    }
+
+    pub fn url<I: Into<String>>(mut self, url: I) -> AuthorBuilder {
+        self.0.url = Some(url.into());
+        self
+    }
+
+    pub fn avatar<I: Into<String>>(mut self, avatar: I) -> AuthorBuilder {
+        self.0.avatar = Some(avatar.into());
+        self
+    }
+
+    pub fn build(self) -> Author {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_feed() {
+        let feed = Builder::new()
+            .version(Version::V1_1)
+            .title("my feed")
+            .home_page_url("https://example.com/")
+            .feed_url("https://example.com/feed.json")
+            .description("a feed")
+            .user_comment("for feed readers")
+            .next_url("https://example.com/feed.json?page=2")
+            .icon("https://example.com/icon.png")
+            .favicon("https://example.com/favicon.ico")
+            .author(Author::builder().name("bob jones").build())
+            .authors(vec![Author::builder().name("jane doe").build()])
+            .language("en")
+            .expired(false)
+            .hub("WebSub", "https://example.com/hub")
+            .items(vec![Item::default()])
+            .extension("_ourapp", Value::Bool(true))
+            .build()
+            .unwrap();
+        assert_eq!(
+            feed,
+            Feed {
+                version: Version::V1_1,
+                title: "my feed".to_string(),
+                items: vec![Item::default()],
+                home_page_url: Some("https://example.com/".to_string()),
+                feed_url: Some("https://example.com/feed.json".to_string()),
+                description: Some("a feed".to_string()),
+                user_comment: Some("for feed readers".to_string()),
+                next_url: Some("https://example.com/feed.json?page=2".to_string()),
+                icon: Some("https://example.com/icon.png".to_string()),
+                favicon: Some("https://example.com/favicon.ico".to_string()),
+                author: Some(Author::builder().name("bob jones").build()),
+                authors: Some(vec![Author::builder().name("jane doe").build()]),
+                language: Some("en".to_string()),
+                expired: Some(false),
+                hubs: Some(vec![Hub::new("WebSub", "https://example.com/hub")]),
+                extensions: vec![("_ourapp".to_string(), Value::Bool(true))].into_iter().collect(),
+            }
+        );
+    }
+
+    #[test]
+    fn build_feed_errors() {
+        match Builder::new().build() {
+            Err(Error::MissingField { field, .. }) => assert_eq!(field, "title"),
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+        assert!(Builder::new().title("t").version("https://jsonfeed.org/version/2").build().is_err());
+        assert!(Builder::new().title("t").extension("ourapp", Value::Null).build().is_err());
+    }
+
+    #[test]
+    fn build_item() {
+        let item = ItemBuilder::new()
+            .id("1")
+            .url("https://example.com/1")
+            .external_url("https://example.org/")
+            .title("title")
+            .content_text("text")
+            .summary("summary")
+            .image("https://example.com/1.png")
+            .banner_image("https://example.com/banner.png")
+            .author(Author::builder().name("bob jones").build())
+            .tag("rust")
+            .tags(vec!["json", "feed"])
+            .language("en")
+            .extension("_ourapp", Value::Bool(true))
+            .build()
+            .unwrap();
+        assert_eq!(item.id, "1");
+        assert_eq!(item.url, Some("https://example.com/1".to_string()));
+        assert_eq!(item.external_url, Some("https://example.org/".to_string()));
+        assert_eq!(item.summary, Some("summary".to_string()));
+        assert_eq!(item.image, Some("https://example.com/1.png".to_string()));
+        assert_eq!(item.banner_image, Some("https://example.com/banner.png".to_string()));
+        assert_eq!(item.author, Some(Author::builder().name("bob jones").build()));
+        assert_eq!(item.tags, Some(vec!["rust".to_string(), "json".to_string(), "feed".to_string()]));
+        assert_eq!(item.language, Some("en".to_string()));
+        assert_eq!(item.extensions.len(), 1);
+    }
+
+    #[test]
+    fn build_typed_item() {
+        let item = TypedItemBuilder::new()
+            .id("1")
+            .title("title")
+            .content_html("<p>html</p>")
+            .tag("rust")
+            .content_text("text")
+            .build();
+        assert_eq!(item.id, "1");
+        assert_eq!(item.title, Some("title".to_string()));
+        assert_eq!(item.content, Content::Both("<p>html</p>".to_string(), "text".to_string()));
+        assert_eq!(item.tags, Some(vec!["rust".to_string()]));
+    }
+
+    #[test]
+    fn build_item_id_from_url() {
+        let item = ItemBuilder::new()
+            .url("https://example.com/1")
+            .id_from_url()
+            .content_html("<p>hi</p>")
+            .build()
+            .unwrap();
+        assert_eq!(item.id, "https://example.com/1");
+
+        let item = ItemBuilder::new()
+            .id("1")
+            .url("https://example.com/1")
+            .id_from_url()
+            .content_html("<p>hi</p>")
+            .build()
+            .unwrap();
+        assert_eq!(item.id, "1");
+
+        match ItemBuilder::new().id_from_url().content_text("").build() {
+            Err(Error::MissingField { field, .. }) => assert_eq!(field, "id"),
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn build_item_invalid_date() {
+        let result = ItemBuilder::new()
+            .id("1")
+            .content_text("")
+            .date_modified("Tue, 10 Jun 2003 04:00:00 GMT")
+            .build();
+        match result {
+            Err(Error::InvalidValue { location, .. }) => assert_eq!(location.path, "date_modified"),
+            other => panic!("expected an invalid value error, got {:?}", other),
+        }
+
+        assert!(TypedItemBuilder::new().date_published("yesterday").is_err());
+        let item = TypedItemBuilder::new()
+            .date_published("2017-04-26T01:09:45+00:00").unwrap()
+            .id("1")
+            .content_text("")
+            .build();
+        assert_eq!(item.date_published, Some("2017-04-26T01:09:45+00:00".to_string()));
+    }
+}
@@ -0,0 +1,87 @@
+//! Reports of what was lost converting a feed to or from another format
+
+use std::fmt;
+
+#[cfg(any(feature = "rss", feature = "atom"))]
+use extensions::Extensions;
+
+/// Something that didn't survive a conversion intact
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionWarning {
+    /// A value with no equivalent in the target format was left out
+    Unmapped { path: String },
+    /// A value was converted, but not exactly, e.g. a required value
+    /// that was missing and had to be filled in
+    Lossy { path: String, message: String },
+}
+
+impl ConversionWarning {
+    /// The path of the value in the source document, e.g. `items[3].image`
+    pub fn path(&self) -> &str {
+        match *self {
+            ConversionWarning::Unmapped { ref path } |
+            ConversionWarning::Lossy { ref path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for ConversionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConversionWarning::Unmapped { ref path } => {
+                write!(f, "{}: no equivalent in the target format, left out", path)
+            },
+            ConversionWarning::Lossy { ref path, ref message } => {
+                write!(f, "{}: {}", path, message)
+            },
+        }
+    }
+}
+
+/// What was lost while converting a feed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConversionReport {
+    pub warnings: Vec<ConversionWarning>,
+}
+
+impl ConversionReport {
+    /// Returns true if the feed was converted without losing anything
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    #[cfg(any(feature = "rss", feature = "atom"))]
+    pub(crate) fn unmapped<P: Into<String>>(&mut self, path: P) {
+        self.warnings.push(ConversionWarning::Unmapped { path: path.into() });
+    }
+
+    #[cfg(any(feature = "rss", feature = "atom"))]
+    pub(crate) fn lossy<P: Into<String>, M: Into<String>>(&mut self, path: P, message: M) {
+        self.warnings.push(ConversionWarning::Lossy { path: path.into(), message: message.into() });
+    }
+
+    /// Reports every extension key as unmapped
+    #[cfg(any(feature = "rss", feature = "atom"))]
+    pub(crate) fn unmapped_extensions(&mut self, prefix: &str, extensions: &Extensions) {
+        for key in extensions.keys() {
+            self.unmapped(format!("{}{}", prefix, key));
+        }
+    }
+}
+
+/// An id for an imported item that has none, from the 64-bit FNV-1a hash
+/// of some of its values
+///
+/// The hash is stable across platforms and releases, so importing the
+/// same document twice gives the same ids.
+#[cfg(any(feature = "rss", feature = "atom"))]
+pub(crate) fn derived_id(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for byte in part.bytes().chain(Some(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,190 @@
+//! The `date_published` and `date_modified` attributes of an item
+//!
+//! Dates are kept as the strings found in the feed, which the spec
+//! requires to be RFC 3339 timestamps. They are only checked on request,
+//! see `ParseOptions::check_dates` and `Feed::validate`. With the `chrono`
+//! feature enabled, items also offer them as `chrono::DateTime<FixedOffset>`
+//! values, and the item builders accept `chrono::DateTime`s.
+
+#[cfg(feature = "chrono")]
+use std::fmt::Display;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+#[cfg(feature = "chrono")]
+use item::Item;
+
+/// A value the item builders accept as a date
+pub trait IntoDate {
+    /// The date as it is written in a feed
+    fn into_date(self) -> String;
+}
+
+impl IntoDate for String {
+    fn into_date(self) -> String {
+        self
+    }
+}
+
+impl IntoDate for &str {
+    fn into_date(self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: TimeZone> IntoDate for DateTime<Tz>
+    where Tz::Offset: Display
+{
+    fn into_date(self) -> String {
+        self.to_rfc3339()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Item {
+    /// `date_published` as a `DateTime`, or `None` if it is absent or
+    /// isn't an RFC 3339 timestamp
+    pub fn date_published_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        self.date_published.as_ref().and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+    }
+
+    /// `date_modified` as a `DateTime`, or `None` if it is absent or
+    /// isn't an RFC 3339 timestamp
+    pub fn date_modified_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        self.date_modified.as_ref().and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+    }
+}
+
+/// Checks that a date is an RFC 3339 timestamp
+pub fn check(d: &str) -> Result<(), String> {
+    match timestamp(d) {
+        Some(_) => Ok(()),
+        None => Err(format!("'{}' is not an RFC 3339 timestamp", d)),
+    }
+}
+
+/// Seconds since the Unix epoch, used to order dates
+///
+/// Returns `None` if the date isn't an RFC 3339 timestamp.
+pub fn timestamp(d: &str) -> Option<i64> {
+    fn num(s: &str) -> Option<i64> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        s.parse().ok()
+    }
+
+    if d.len() < 20 || !d.is_char_boundary(19) {
+        return None;
+    }
+    let (datetime, rest) = d.split_at(19);
+    let b = datetime.as_bytes();
+    if b[4] != b'-' || b[7] != b'-' || (b[10] != b'T' && b[10] != b't' && b[10] != b' ') ||
+            b[13] != b':' || b[16] != b':' {
+        return None;
+    }
+    let year = num(&datetime[0..4])?;
+    let month = num(&datetime[5..7])?;
+    let day = num(&datetime[8..10])?;
+    let hour = num(&datetime[11..13])?;
+    let minute = num(&datetime[14..16])?;
+    let second = num(&datetime[17..19])?;
+    // leap seconds are left out, as neither chrono nor Unix time can
+    // represent them
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) ||
+            hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // skip fractional seconds
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let rest = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+            if rest.len() == fraction.len() {
+                return None;
+            }
+            rest
+        },
+        None => rest,
+    };
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.is_ascii() && rest.as_bytes()[3] == b':' => {
+            let (hours, minutes) = (num(&rest[1..3])?, num(&rest[4..6])?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let minutes = hours * 60 + minutes;
+            match &rest[..1] {
+                "+" => minutes * 60,
+                "-" => -minutes * 60,
+                _ => return None,
+            }
+        },
+        _ => return None,
+    };
+
+    // days since the epoch, from Howard Hinnant's `days_from_civil`
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp("2017-04-26T01:09:45+00:00"), Some(1493168985));
+        assert_eq!(timestamp("2017-04-26T03:09:45.123+02:00"), Some(1493168985));
+        assert_eq!(timestamp("1969-12-31T23:59:59Z"), Some(-1));
+        assert_eq!(timestamp("2016-02-29T00:00:00Z"), Some(1456704000));
+        assert_eq!(timestamp("2000-02-29T00:00:00Z"), Some(951782400));
+    }
+
+    #[test]
+    fn invalid_timestamps() {
+        assert_eq!(timestamp("yesterday"), None);
+        assert_eq!(timestamp("2017-13-26T01:09:45Z"), None);
+        assert_eq!(timestamp("2017-04-26T01:09:45"), None);
+        assert_eq!(timestamp("2017-02-31T00:00:00Z"), None);
+        assert_eq!(timestamp("2017-02-29T00:00:00Z"), None);
+        assert_eq!(timestamp("1900-02-29T00:00:00Z"), None);
+        assert_eq!(timestamp("2017-04-31T00:00:00Z"), None);
+        assert_eq!(timestamp("2016-12-31T23:59:60Z"), None);
+        assert_eq!(timestamp("2017-04-26T01:09:45.Z"), None);
+        assert_eq!(timestamp("2017-04-26T01:09:45+24:00"), None);
+        assert!(check("yesterday").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn parsed_dates() {
+        use chrono::Utc;
+
+        let mut item = Item { date_published: Some("2017-04-26T01:09:45+02:00".to_string()), ..Item::default() };
+        assert_eq!(item.date_published_parsed().unwrap(), Utc.with_ymd_and_hms(2017, 4, 25, 23, 9, 45).unwrap());
+        assert_eq!(item.date_modified_parsed(), None);
+        item.date_published = Some("yesterday".to_string());
+        assert_eq!(item.date_published_parsed(), None);
+        assert_eq!(Utc.with_ymd_and_hms(2017, 4, 25, 23, 9, 45).unwrap().into_date(), "2017-04-25T23:09:45+00:00");
+    }
+}
@@ -0,0 +1,205 @@
+//! What changed between two versions of a feed
+
+use std::collections::{BTreeSet, HashMap};
+
+use feed::Feed;
+use item::Item;
+use extensions::Extensions;
+
+/// The differences between two versions of a feed, returned by
+/// `Feed::diff`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedDiff<'a> {
+    /// Items only in the new feed, in its order
+    pub added: Vec<&'a Item>,
+    /// Items only in the old feed, in its order
+    pub removed: Vec<&'a Item>,
+    /// Items in both feeds that differ, in the new feed's order
+    pub modified: Vec<ItemDiff<'a>>,
+    /// The feed-level fields that changed, e.g. `title` or `next_url`,
+    /// followed by any changed extension keys
+    pub metadata: Vec<String>,
+}
+
+impl<'a> FeedDiff<'a> {
+    /// Returns true if the feeds are the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() &&
+            self.modified.is_empty() && self.metadata.is_empty()
+    }
+}
+
+/// An item that differs between two versions of a feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDiff<'a> {
+    pub old: &'a Item,
+    pub new: &'a Item,
+    /// The fields that changed, e.g. `content_html` or `date_modified`,
+    /// followed by any changed extension keys
+    pub fields: Vec<String>,
+}
+
+/// Pushes the name of every listed field that differs
+macro_rules! changed_fields {
+    ($fields:expr, $old:expr, $new:expr, $($field:ident),*) => {
+        $(
+            if $old.$field != $new.$field {
+                $fields.push(stringify!($field).to_string());
+            }
+        )*
+    }
+}
+
+impl Feed {
+    /// Compares two versions of a feed, matching items by id
+    ///
+    /// If several items share an id, only the first of them is compared.
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::{Feed, Item};
+    /// # fn main() {
+    /// let old = Feed::builder().title("feed")
+    ///     .item(Item::builder().id("1").content_text("first").build().unwrap())
+    ///     .item(Item::builder().id("2").content_text("second").build().unwrap())
+    ///     .build().unwrap();
+    /// let new = Feed::builder().title("my feed")
+    ///     .item(Item::builder().id("2").content_text("second, edited").build().unwrap())
+    ///     .item(Item::builder().id("3").content_text("third").build().unwrap())
+    ///     .build().unwrap();
+    ///
+    /// let diff = Feed::diff(&old, &new);
+    /// assert_eq!(diff.added[0].id, "3");
+    /// assert_eq!(diff.removed[0].id, "1");
+    /// assert_eq!(diff.modified[0].fields, vec!["content_text"]);
+    /// assert_eq!(diff.metadata, vec!["title"]);
+    /// # }
+    /// ```
+    pub fn diff<'a>(old: &'a Feed, new: &'a Feed) -> FeedDiff<'a> {
+        let old_items = by_id(&old.items);
+        let new_items = by_id(&new.items);
+
+        let mut added = vec![];
+        let mut modified = vec![];
+        for (i, item) in new.items.iter().enumerate() {
+            match old_items.get(&item.id[..]) {
+                None => added.push(item),
+                Some(_) if new_items[&item.id[..]] != i => {},
+                Some(&j) => {
+                    let fields = item_fields(&old.items[j], item);
+                    if !fields.is_empty() {
+                        modified.push(ItemDiff { old: &old.items[j], new: item, fields });
+                    }
+                },
+            }
+        }
+        let removed = old.items.iter()
+            .filter(|item| !new_items.contains_key(&item.id[..]))
+            .collect();
+
+        let mut metadata = vec![];
+        changed_fields!(metadata, old, new,
+                        version, title, home_page_url, feed_url, description, user_comment, next_url,
+                        icon, favicon, author, authors, language, expired, hubs);
+        extension_keys(&mut metadata, &old.extensions, &new.extensions);
+
+        FeedDiff { added, removed, modified, metadata }
+    }
+}
+
+/// The index of the first item with each id
+fn by_id(items: &[Item]) -> HashMap<&str, usize> {
+    let mut ids = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        ids.entry(&item.id[..]).or_insert(i);
+    }
+    ids
+}
+
+fn item_fields(old: &Item, new: &Item) -> Vec<String> {
+    let mut fields = vec![];
+    changed_fields!(fields, old, new, url, external_url, title);
+    if old.content.html() != new.content.html() {
+        fields.push("content_html".to_string());
+    }
+    if old.content.text() != new.content.text() {
+        fields.push("content_text".to_string());
+    }
+    changed_fields!(fields, old, new,
+                    summary, image, banner_image, date_published, date_modified, author, authors,
+                    tags, language, attachments);
+    extension_keys(&mut fields, &old.extensions, &new.extensions);
+    fields
+}
+
+fn extension_keys(fields: &mut Vec<String>, old: &Extensions, new: &Extensions) {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    fields.extend(keys.into_iter().filter(|key| old.get(*key) != new.get(*key)).cloned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feed::Author;
+
+    fn item(id: &str, text: &str) -> Item {
+        Item::builder().id(id).content_text(text).build().unwrap()
+    }
+
+    fn ids(items: &[&Item]) -> Vec<String> {
+        items.iter().map(|item| item.id.clone()).collect()
+    }
+
+    #[test]
+    fn diff_items() {
+        let mut old = Feed {
+            items: vec![item("1", "one"), item("2", "two"), item("3", "three"), item("4", "four")],
+            ..Feed::default()
+        };
+        let mut new = Feed {
+            items: vec![item("5", "five"), item("3", "three"), item("2", "two"), item("4", "four")],
+            ..Feed::default()
+        };
+
+        new.items[2].content = new.items[2].content.clone().with_html("<p>two</p>");
+        new.items[2].date_modified = Some("2017-05-17T10:02:12-04:00".to_string());
+        new.items[2].author = Some(Author::builder().name("bob").build());
+        new.items[2].set_extension("_ourapp", 1).unwrap();
+        old.items[3].set_extension("_ourapp", 1).unwrap();
+
+        let diff = Feed::diff(&old, &new);
+        assert_eq!(ids(&diff.added), vec!["5"]);
+        assert_eq!(ids(&diff.removed), vec!["1"]);
+        assert_eq!(diff.modified.len(), 2);
+        assert_eq!(diff.modified[0].new.id, "2");
+        assert_eq!(diff.modified[0].fields, vec!["content_html", "date_modified", "author", "_ourapp"]);
+        assert_eq!(diff.modified[1].new.id, "4");
+        assert_eq!(diff.modified[1].fields, vec!["_ourapp"]);
+        assert!(diff.metadata.is_empty());
+    }
+
+    #[test]
+    fn diff_metadata() {
+        let old = Feed {
+            title: "feed".to_string(),
+            next_url: Some("https://example.com/2.json".to_string()),
+            ..Feed::default()
+        };
+        let mut new = Feed {
+            title: "my feed".to_string(),
+            icon: Some("https://example.com/icon.png".to_string()),
+            ..Feed::default()
+        };
+        new.set_extension("_x", true).unwrap();
+        assert_eq!(Feed::diff(&old, &new).metadata, vec!["title", "next_url", "icon", "_x"]);
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        let feed = Feed {
+            items: vec![item("1", "one"), item("1", "duplicate")],
+            ..Feed::default()
+        };
+        assert!(Feed::diff(&feed, &feed.clone()).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! A minimal element tree for reading the XML-based feed formats, and
+//! helpers for writing them
+
+use std::io::{Read, Write};
+
+use xml::reader::{EventReader, XmlEvent};
+use xml::writer::{self, EventWriter};
+
+use errors::*;
+
+/// An XML element, with its text and child elements
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Element {
+    pub namespace: Option<String>,
+    pub name: String,
+    /// The name as written in the document, e.g. `dc:creator`
+    pub qualified: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+}
+
+impl Element {
+    /// Returns true if the element has the given namespace and local name
+    pub fn is(&self, namespace: Option<&str>, name: &str) -> bool {
+        self.namespace.as_ref().map(|s| &s[..]) == namespace && self.name == name
+    }
+
+    /// The first child with the given namespace and local name
+    pub fn child(&self, namespace: Option<&str>, name: &str) -> Option<&Element> {
+        self.children.iter().find(|e| e.is(namespace, name))
+    }
+
+    /// The trimmed text of the first child with the given namespace and
+    /// local name, if it isn't empty
+    #[cfg_attr(not(feature = "rss"), allow(dead_code))]
+    pub fn child_text(&self, namespace: Option<&str>, name: &str) -> Option<String> {
+        self.child(namespace, name).and_then(Element::trimmed)
+    }
+
+    /// The value of an attribute, by its name as written, e.g. `xml:lang`
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|a| a.0 == name).map(|a| &a.1[..])
+    }
+
+    /// The element's text, trimmed, if it isn't empty
+    pub fn trimmed(&self) -> Option<String> {
+        let text = self.text.trim();
+        if text.is_empty() { None } else { Some(text.to_string()) }
+    }
+}
+
+/// Reads a document into a tree, returning the root element
+pub(crate) fn parse<R: Read>(reader: R) -> Result<Element> {
+    let mut stack: Vec<Element> = vec![];
+    for event in EventReader::new(reader) {
+        match event? {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                let qualified = match name.prefix {
+                    Some(ref prefix) => format!("{}:{}", prefix, name.local_name),
+                    None => name.local_name.clone(),
+                };
+                stack.push(Element {
+                    namespace: name.namespace,
+                    name: name.local_name,
+                    qualified,
+                    attributes: attributes.into_iter()
+                        .map(|a| {
+                            let name = match a.name.prefix {
+                                Some(prefix) => format!("{}:{}", prefix, a.name.local_name),
+                                None => a.name.local_name,
+                            };
+                            (name, a.value)
+                        })
+                        .collect(),
+                    children: vec![],
+                    text: String::new(),
+                });
+            },
+            XmlEvent::EndElement { .. } => {
+                let element = stack.pop().expect("xml-rs balances elements");
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            },
+            XmlEvent::Characters(s) | XmlEvent::CData(s) | XmlEvent::Whitespace(s) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&s);
+                }
+            },
+            _ => {},
+        }
+    }
+    Err(Error::Xml { message: "the document has no root element".to_string(), location: Location::default() })
+}
+
+/// Writes an element containing only text
+pub(crate) fn write_text<W: Write>(w: &mut EventWriter<W>, name: &str, text: &str) -> Result<()> {
+    w.write(writer::XmlEvent::start_element(name))?;
+    w.write(writer::XmlEvent::characters(text))?;
+    w.write(writer::XmlEvent::end_element())?;
+    Ok(())
+}
+
+/// Writes an element containing only a CDATA section
+#[cfg(feature = "rss")]
+pub(crate) fn write_cdata<W: Write>(w: &mut EventWriter<W>, name: &str, text: &str) -> Result<()> {
+    w.write(writer::XmlEvent::start_element(name))?;
+    w.write(writer::XmlEvent::cdata(text))?;
+    w.write(writer::XmlEvent::end_element())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree() {
+        let xml = r#"<a xmlns:x="urn:x" href="h" xml:lang="en"><x:b> one &amp; <![CDATA[<two>]]> </x:b><c/></a>"#;
+        let root = parse(xml.as_bytes()).unwrap();
+        assert_eq!(root.attr("href"), Some("h"));
+        assert_eq!(root.attr("xml:lang"), Some("en"));
+        let b = root.child(Some("urn:x"), "b").unwrap();
+        assert_eq!(b.qualified, "x:b");
+        assert_eq!(b.trimmed(), Some("one & <two>".to_string()));
+        assert!(root.child(None, "b").is_none());
+        assert_eq!(root.child_text(None, "c"), None);
+    }
+
+    #[test]
+    fn malformed() {
+        match parse("<a>\n<b></a>".as_bytes()) {
+            Err(Error::Xml { location, .. }) => assert_eq!(location.line, Some(2)),
+            other => panic!("expected an xml error, got {:?}", other),
+        }
+    }
+}
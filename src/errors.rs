@@ -1,7 +1,406 @@
+use std::fmt;
+use std::io;
+use std::error;
+
+use serde::de;
 use serde_json;
-error_chain!{
-    foreign_links {
-        Serde(serde_json::Error);
+use serde_json::error::Category;
+use serde_path_to_error;
+use serde_path_to_error::Segment;
+#[cfg(feature = "xml-rs")]
+use xml;
+
+/// Where in a JSON Feed document an error was found
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Location {
+    /// The path of the offending value, e.g. `items[17].attachments[0].url`.
+    /// Empty for the document itself.
+    pub path: String,
+    /// The 1-based line, when parsing from text
+    pub line: Option<usize>,
+    /// The 1-based column, when parsing from text
+    pub column: Option<usize>,
+}
+
+impl Location {
+    /// A location known only by its path
+    pub fn path<P: Into<String>>(path: P) -> Location {
+        Location { path: path.into(), line: None, column: None }
+    }
+
+    fn from_json(path: String, e: &serde_json::Error) -> Location {
+        // serde_json reports line 0 when there is no position, e.g. for
+        // errors from `from_value`
+        let (line, column) = match e.line() {
+            0 => (None, None),
+            line => (Some(line), Some(e.column())),
+        };
+        Location { path, line, column }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = if self.path.is_empty() { "." } else { &self.path[..] };
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{} (line {}, column {})", path, line, column),
+            _ => f.write_str(path),
+        }
+    }
+}
+
+/// The error type for this crate
+#[derive(Debug)]
+pub enum Error {
+    /// A required field is missing. The path is where the field should be,
+    /// e.g. `items[1].id`.
+    MissingField { field: String, location: Location },
+    /// A value has the wrong type or doesn't make sense, e.g. an invalid URL
+    InvalidValue { message: String, location: Location },
+    /// A field appears twice in the same object. The path is the field's.
+    DuplicateField { field: String, location: Location },
+    /// The feed declares a version this crate doesn't know
+    UnsupportedVersion { version: String },
+    /// An argument passed to this crate is invalid, e.g. a page size of 0
+    InvalidArgument { name: String, message: String },
+    /// Reading or writing failed
+    Io(io::Error),
+    /// The document isn't valid JSON
+    Json { error: serde_json::Error, location: Location },
+    /// Reading or writing another feed format's XML failed
+    Xml { message: String, location: Location },
+}
+
+/// A `Result` alias where the `Err` case is `jsonfeed::Error`
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+impl Error {
+    /// Where the error was found, if it relates to a part of the feed
+    pub fn location(&self) -> Option<&Location> {
+        match *self {
+            Error::MissingField { ref location, .. } |
+            Error::InvalidValue { ref location, .. } |
+            Error::DuplicateField { ref location, .. } |
+            Error::Json { ref location, .. } |
+            Error::Xml { ref location, .. } => Some(location),
+            Error::UnsupportedVersion { .. } |
+            Error::InvalidArgument { .. } |
+            Error::Io(_) => None,
+        }
+    }
+
+    /// The path of the offending value, e.g. `items[17].attachments[0].url`
+    pub fn path(&self) -> Option<&str> {
+        match *self {
+            Error::UnsupportedVersion { .. } => Some("version"),
+            _ => self.location().map(|l| &l.path[..]),
+        }
+    }
+
+    /// The line of the offending value, when parsing from text
+    pub fn line(&self) -> Option<usize> {
+        self.location().and_then(|l| l.line)
+    }
+
+    /// The column of the offending value, when parsing from text
+    pub fn column(&self) -> Option<usize> {
+        self.location().and_then(|l| l.column)
+    }
+
+    pub(crate) fn invalid_value<M: Into<String>, P: Into<String>>(message: M, path: P) -> Error {
+        Error::InvalidValue { message: message.into(), location: Location::path(path) }
+    }
+
+    pub(crate) fn invalid_argument<N: Into<String>, M: Into<String>>(name: N, message: M) -> Error {
+        Error::InvalidArgument { name: name.into(), message: message.into() }
+    }
+
+    pub(crate) fn missing_field<F: Into<String>>(field: F) -> Error {
+        let field = field.into();
+        Error::MissingField { location: Location::path(field.clone()), field }
+    }
+
+    /// Moves an error in a value that was parsed on its own to where the
+    /// value is in the feed: under `prefix`, starting at `line` and
+    /// `column`
+    pub(crate) fn nested(mut self, prefix: &str, line: usize, column: usize) -> Error {
+        match self {
+            Error::MissingField { ref mut location, .. } |
+            Error::InvalidValue { ref mut location, .. } |
+            Error::DuplicateField { ref mut location, .. } |
+            Error::Json { ref mut location, .. } |
+            Error::Xml { ref mut location, .. } => {
+                location.path = if location.path.is_empty() {
+                    prefix.to_string()
+                } else {
+                    format!("{}.{}", prefix, location.path)
+                };
+                if let (Some(l), Some(c)) = (location.line, location.column) {
+                    location.column = Some(if l == 1 { column + c - 1 } else { c });
+                    location.line = Some(line + l - 1);
+                }
+            },
+            Error::UnsupportedVersion { .. } |
+            Error::InvalidArgument { .. } |
+            Error::Io(_) => {},
+        }
+        self
+    }
+
+    fn from_json(mut path: String, e: serde_json::Error) -> Error {
+        match e.classify() {
+            Category::Io => Error::Io(e.into()),
+            Category::Syntax | Category::Eof => {
+                let location = Location::from_json(path, &e);
+                Error::Json { error: e, location }
+            },
+            Category::Data => {
+                let message = without_position(&e);
+                if let Some(field) = marked_field(&message, MISSING) {
+                    push_key(&mut path, field);
+                    let location = Location::from_json(path, &e);
+                    return Error::MissingField { field: field.to_string(), location };
+                }
+                if let Some(field) = marked_field(&message, DUPLICATE) {
+                    push_key(&mut path, field);
+                    let location = Location::from_json(path, &e);
+                    return Error::DuplicateField { field: field.to_string(), location };
+                }
+                let location = Location::from_json(path, &e);
+                Error::InvalidValue { message, location }
+            },
+        }
+    }
+}
+
+/// Starts the messages of the missing and duplicate field errors raised by
+/// this crate's visitors. serde_json errors only keep a message, so this
+/// is how `Error::from_json` tells them apart. `json_message` strips it.
+const MARKER: char = '\u{0}';
+const MISSING: &str = "missing field `";
+const DUPLICATE: &str = "duplicate field `";
+
+/// The field named by a marked error message of the given kind
+fn marked_field<'a>(message: &'a str, kind: &str) -> Option<&'a str> {
+    message.strip_prefix(MARKER)?.strip_prefix(kind)?.strip_suffix('`')
+}
+
+fn without_position(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    if e.line() == 0 {
+        return message;
+    }
+    let suffix = format!(" at line {} column {}", e.line(), e.column());
+    match message.rfind(&suffix) {
+        Some(i) if i + suffix.len() == message.len() => message[..i].to_string(),
+        _ => message,
+    }
+}
+
+/// The message of a serde_json data error, without the position or marker
+pub(crate) fn json_message(e: &serde_json::Error) -> String {
+    let message = without_position(e);
+    match message.strip_prefix(MARKER) {
+        Some(stripped) => stripped.to_string(),
+        None => message,
+    }
+}
+
+/// A `de::Error` for a missing field, which converts to
+/// `Error::MissingField`
+pub(crate) fn missing_field_error<E: de::Error>(field: &str) -> E {
+    E::custom(format_args!("{}{}{}`", MARKER, MISSING, field))
+}
+
+/// A `de::Error` for a field that appears twice, which converts to
+/// `Error::DuplicateField`
+pub(crate) fn duplicate_field_error<E: de::Error>(field: &str) -> E {
+    E::custom(format_args!("{}{}{}`", MARKER, DUPLICATE, field))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::MissingField { ref field, ref location } => {
+                write!(f, "missing field `{}` at {}", field, location)
+            },
+            Error::InvalidValue { ref message, ref location } => {
+                write!(f, "{} at {}", message, location)
+            },
+            Error::DuplicateField { ref field, ref location } => {
+                write!(f, "duplicate field `{}` at {}", field, location)
+            },
+            Error::UnsupportedVersion { ref version } => {
+                write!(f, "unsupported JSON Feed version: '{}'", version)
+            },
+            Error::InvalidArgument { ref name, ref message } => {
+                write!(f, "invalid argument `{}`: {}", name, message)
+            },
+            Error::Io(ref e) => write!(f, "{}", e),
+            Error::Json { ref error, .. } => write!(f, "{}", error),
+            Error::Xml { ref message, ref location } => {
+                match (location.line, location.column) {
+                    (Some(line), Some(column)) if location.path.is_empty() => {
+                        write!(f, "{} at line {}, column {}", message, line, column)
+                    },
+                    _ if location.path.is_empty() => f.write_str(message),
+                    _ => write!(f, "{} at {}", message, location),
+                }
+            },
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Json { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error::from_json(String::new(), e)
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(e: serde_path_to_error::Error<serde_json::Error>) -> Error {
+        let mut path = String::new();
+        for segment in e.path().iter() {
+            match *segment {
+                Segment::Seq { index } => path.push_str(&format!("[{}]", index)),
+                Segment::Map { ref key } => push_key(&mut path, key),
+                Segment::Enum { ref variant } => push_key(&mut path, variant),
+                Segment::Unknown => push_key(&mut path, "?"),
+            }
+        }
+        Error::from_json(path, e.into_inner())
+    }
+}
+
+#[cfg(feature = "xml-rs")]
+impl From<xml::writer::Error> for Error {
+    fn from(e: xml::writer::Error) -> Error {
+        match e {
+            xml::writer::Error::Io(e) => Error::Io(e),
+            e => Error::Xml { message: e.to_string(), location: Location::default() },
+        }
+    }
+}
+
+#[cfg(feature = "xml-rs")]
+impl From<xml::reader::Error> for Error {
+    fn from(e: xml::reader::Error) -> Error {
+        use xml::common::Position;
+
+        let position = e.position();
+        let location = Location {
+            path: String::new(),
+            line: Some(position.row as usize + 1),
+            column: Some(position.column as usize + 1),
+        };
+        Error::Xml { message: e.msg().to_string(), location }
+    }
+}
+
+fn push_key(path: &mut String, key: &str) {
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Error {
+        let de = &mut serde_json::Deserializer::from_str(json);
+        let result: ::std::result::Result<::Feed, _> = serde_path_to_error::deserialize(de);
+        result.unwrap_err().into()
+    }
+
+    #[test]
+    fn missing_field() {
+        let err = parse(r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"id":"1","content_text":""},{"content_text":""}]}"#);
+        match err {
+            Error::MissingField { ref field, ref location } => {
+                assert_eq!(field, "id");
+                assert_eq!(location.path, "items[1].id");
+                assert_eq!(location.line, Some(1));
+            },
+            ref other => panic!("expected a missing field error, got {:?}", other),
+        }
+        assert_eq!(err.to_string(), "missing field `id` at items[1].id (line 1, column 112)");
+    }
+
+    #[test]
+    fn duplicate_field() {
+        let err = parse(r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"id":"1","id":"2","content_text":""}]}"#);
+        match err {
+            Error::DuplicateField { field, location } => {
+                assert_eq!(field, "id");
+                assert_eq!(location.path, "items[0].id");
+            },
+            other => panic!("expected a duplicate field error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn json_message_is_unmarked() {
+        let e = serde_json::from_str::<::Item>("{\n\"content_text\": \"\"}").unwrap_err();
+        assert_eq!(json_message(&e), "missing field `id`");
+    }
+
+    #[test]
+    fn nested_field_errors() {
+        let err = parse(r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"id":"1","content_text":"","attachments":[{"url":"https://example.com/a.mp3"}]}]}"#);
+        match err {
+            Error::MissingField { field, location } => {
+                assert_eq!(field, "mime_type");
+                assert_eq!(location.path, "items[0].attachments[0].mime_type");
+            },
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+
+        let err = parse(r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[],"hubs":[{"type":"WebSub","url":"a","_x":1,"_x":2}]}"#);
+        match err {
+            Error::DuplicateField { field, location } => {
+                assert_eq!(field, "_x");
+                assert_eq!(location.path, "hubs[0]._x");
+            },
+            other => panic!("expected a duplicate field error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_value() {
+        let err = parse("{\"version\":\"https://jsonfeed.org/version/1\",\"title\":\"\",\n\"items\":[{\"id\":\"1\",\"content_text\":\"\",\"attachments\":[{\"url\":5}]}]}");
+        match err {
+            Error::InvalidValue { location, .. } => {
+                assert_eq!(location.path, "items[0].attachments[0].url");
+                assert_eq!(location.line, Some(2));
+            },
+            other => panic!("expected an invalid value error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn syntax_error() {
+        let err = parse(r#"{"version":"#);
+        match err {
+            Error::Json { location, .. } => assert_eq!(location.line, Some(1)),
+            other => panic!("expected a json error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Support for custom extension objects
+//!
+//! The JSON Feed spec allows publishers to add their own keys to feeds,
+//! items, authors, attachments and hubs, as long as the key starts with
+//! an underscore.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Custom `_`-prefixed keys of a feed, item, author, attachment or hub
+pub type Extensions = BTreeMap<String, Value>;
+
+/// Returns true if `key` may be used as an extension key
+pub fn is_extension_key(key: &str) -> bool {
+    key.starts_with('_')
+}
+
+/// Adds typed accessors for the `extensions` field of a struct
+macro_rules! impl_extensions {
+    ($t:ty) => {
+        impl $t {
+            /// Deserializes the extension object stored under `key`
+            ///
+            /// Returns `None` if there is no such extension.
+            pub fn extension<T>(&self, key: &str) -> Option<$crate::errors::Result<T>>
+                where T: ::serde::de::DeserializeOwned
+            {
+                self.extensions.get(key).map(|v| {
+                    Ok(::serde_json::from_value(v.clone())?)
+                })
+            }
+
+            /// Stores `value` as the extension object under `key`
+            ///
+            /// The key must start with an underscore. The previous value, if
+            /// any, is returned.
+            pub fn set_extension<T>(&mut self, key: &str, value: T)
+                    -> $crate::errors::Result<Option<::serde_json::Value>>
+                where T: ::serde::ser::Serialize
+            {
+                if !$crate::extensions::is_extension_key(key) {
+                    return Err($crate::errors::Error::invalid_value(
+                        "extension keys must start with an underscore", key));
+                }
+                let value = ::serde_json::to_value(value)?;
+                Ok(self.extensions.insert(key.to_string(), value))
+            }
+
+            /// Removes the extension object stored under `key`
+            pub fn remove_extension(&mut self, key: &str) -> Option<::serde_json::Value> {
+                self.extensions.remove(key)
+            }
+        }
+    }
+}
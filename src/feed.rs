@@ -1,9 +1,32 @@
 use std::default::Default;
 
+use serde::de::{Deserialize, Deserializer};
+
+use borrowed::{FeedRef, AuthorRef, AttachmentRef, HubRef};
+
 use item::Item;
-use builder::Builder;
+use builder::{Builder, AttachmentBuilder, AuthorBuilder};
+use version::Version;
+use extensions::Extensions;
 
-const VERSION_1: &'static str = "https://jsonfeed.org/version/1";
+/// The keys a feed may contain, besides extensions
+pub(crate) const FIELDS: &[&str] = &[
+    "version",
+    "title",
+    "items",
+    "home_page_url",
+    "feed_url",
+    "description",
+    "user_comment",
+    "next_url",
+    "icon",
+    "favicon",
+    "author",
+    "authors",
+    "language",
+    "expired",
+    "hubs",
+];
 
 /// Represents a single feed
 ///
@@ -38,9 +61,9 @@ const VERSION_1: &'static str = "https://jsonfeed.org/version/1";
 /// );
 /// # }
 /// ```
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Feed {
-    pub version: String,
+    pub version: Version,
     pub title: String,
     pub items: Vec<Item>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,22 +83,145 @@ pub struct Feed {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<Author>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<Author>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub expired: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hubs: Option<Vec<Hub>>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
 }
 
+impl_extensions!(Feed);
+
 impl Feed {
     /// Used to construct a Feed object
     pub fn builder() -> Builder {
         Builder::new()
     }
+
+    /// Upgrades the feed to JSON Feed version 1.1
+    ///
+    /// The deprecated `author` of the feed and of every item is moved
+    /// into `authors`, unless it is already listed there.
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::{Feed, Author, Version};
+    /// # fn main() {
+    /// let mut feed = Feed { author: Some(Author::builder().name("bob").build()), ..Feed::default() };
+    /// let report = feed.upgrade_to_1_1();
+    ///
+    /// assert!(report.feed_author_moved);
+    /// assert_eq!(feed.version, Version::V1_1);
+    /// assert_eq!(feed.authors, Some(vec![Author::builder().name("bob").build()]));
+    /// # }
+    /// ```
+    pub fn upgrade_to_1_1(&mut self) -> UpgradeReport {
+        let mut report = UpgradeReport {
+            previous_version: ::std::mem::replace(&mut self.version, Version::V1_1),
+            feed_author_moved: false,
+            feed_author_deduplicated: false,
+            items_upgraded: vec![],
+            items_deduplicated: vec![],
+        };
+        match move_author(&mut self.author, &mut self.authors) {
+            AuthorMove::Moved => report.feed_author_moved = true,
+            AuthorMove::Deduplicated => report.feed_author_deduplicated = true,
+            AuthorMove::None => {},
+        }
+        for item in self.items.iter_mut() {
+            match move_author(&mut item.author, &mut item.authors) {
+                AuthorMove::Moved => report.items_upgraded.push(item.id.clone()),
+                AuthorMove::Deduplicated => report.items_deduplicated.push(item.id.clone()),
+                AuthorMove::None => {},
+            }
+        }
+        report
+    }
+
+    /// A copy of the feed without its items
+    pub(crate) fn metadata(&self) -> Feed {
+        Feed {
+            version: self.version.clone(),
+            title: self.title.clone(),
+            items: vec![],
+            home_page_url: self.home_page_url.clone(),
+            feed_url: self.feed_url.clone(),
+            description: self.description.clone(),
+            user_comment: self.user_comment.clone(),
+            next_url: self.next_url.clone(),
+            icon: self.icon.clone(),
+            favicon: self.favicon.clone(),
+            author: self.author.clone(),
+            authors: self.authors.clone(),
+            language: self.language.clone(),
+            expired: self.expired,
+            hubs: self.hubs.clone(),
+            extensions: self.extensions.clone(),
+        }
+    }
+}
+
+/// What happened to a deprecated `author`
+enum AuthorMove {
+    /// There was no `author`
+    None,
+    /// The `author` was added to `authors`
+    Moved,
+    /// The `author` was already in `authors`, and was removed
+    Deduplicated,
+}
+
+fn move_author(author: &mut Option<Author>, authors: &mut Option<Vec<Author>>) -> AuthorMove {
+    match author.take() {
+        Some(author) => {
+            let authors = authors.get_or_insert_with(Vec::new);
+            if authors.contains(&author) {
+                AuthorMove::Deduplicated
+            } else {
+                authors.insert(0, author);
+                AuthorMove::Moved
+            }
+        },
+        None => AuthorMove::None,
+    }
+}
+
+/// Describes what `Feed::upgrade_to_1_1` changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeReport {
+    /// The version the feed declared before upgrading
+    pub previous_version: Version,
+    /// Whether the feed-level `author` was moved into `authors`
+    pub feed_author_moved: bool,
+    /// Whether the feed-level `author` was removed because it was already
+    /// in `authors`
+    pub feed_author_deduplicated: bool,
+    /// The ids of the items whose `author` was moved into `authors`
+    pub items_upgraded: Vec<String>,
+    /// The ids of the items whose `author` was removed because it was
+    /// already in their `authors`
+    pub items_deduplicated: Vec<String>,
+}
+
+impl UpgradeReport {
+    /// Returns true if the upgrade didn't change anything
+    pub fn is_empty(&self) -> bool {
+        self.previous_version == Version::V1_1 &&
+            !self.feed_author_moved &&
+            !self.feed_author_deduplicated &&
+            self.items_upgraded.is_empty() &&
+            self.items_deduplicated.is_empty()
+    }
 }
 
 impl Default for Feed {
     fn default() -> Feed {
         Feed {
-            version: VERSION_1.to_string(),
+            version: Version::V1,
             title: "".to_string(),
             items: vec![],
             home_page_url: None,
@@ -86,61 +232,197 @@ impl Default for Feed {
             icon: None,
             favicon: None,
             author: None,
+            authors: None,
+            language: None,
             expired: None,
             hubs: None,
+            extensions: Extensions::new(),
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Feed {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        // shares its visitor with `FeedRef`
+        FeedRef::deserialize(deserializer).map(FeedRef::into_owned)
+    }
+}
+
 /// Represents an `attachment` for an item
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Attachment {
-    url: String,
-    mime_type: String,
-    title: Option<String>,
-    size_in_bytes: Option<u64>,
-    duration_in_seconds: Option<u64>,
+    pub(crate) url: String,
+    pub(crate) mime_type: String,
+    pub(crate) title: Option<String>,
+    pub(crate) size_in_bytes: Option<u64>,
+    pub(crate) duration_in_seconds: Option<u64>,
+    #[serde(flatten)]
+    pub(crate) extensions: Extensions,
+}
+
+impl_extensions!(Attachment);
+
+impl Attachment {
+    /// Used to construct an Attachment object
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::Attachment;
+    /// # fn main() {
+    /// let attachment = Attachment::builder("https://example.com/episode-1.mp3", "audio/mpeg")
+    ///                     .size_in_bytes(5236920)
+    ///                     .duration_in_seconds(3600)
+    ///                     .build();
+    /// assert_eq!(attachment.mime_type(), "audio/mpeg");
+    /// assert_eq!(attachment.duration_in_seconds(), Some(3600));
+    /// # }
+    /// ```
+    pub fn builder<U: Into<String>, M: Into<String>>(url: U, mime_type: M) -> AttachmentBuilder {
+        AttachmentBuilder::new(url, mime_type)
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_ref().map(|s| &s[..])
+    }
+
+    pub fn size_in_bytes(&self) -> Option<u64> {
+        self.size_in_bytes
+    }
+
+    pub fn duration_in_seconds(&self) -> Option<u64> {
+        self.duration_in_seconds
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
+impl<'de> Deserialize<'de> for Attachment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        AttachmentRef::deserialize(deserializer).map(AttachmentRef::into_owned)
+    }
 }
 
 /// Represents an `author` in both a feed and a feed item
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
 pub struct Author {
-    name: Option<String>,
-    url: Option<String>,
-    avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) avatar: Option<String>,
+    #[serde(flatten)]
+    pub(crate) extensions: Extensions,
 }
 
+impl_extensions!(Author);
+
 impl Author {
+    /// Used to construct an Author object
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::Author;
+    /// # fn main() {
+    /// let author = Author::builder()
+    ///                 .name("bob jones")
+    ///                 .url("https://example.com/bob")
+    ///                 .build();
+    /// assert_eq!(author.name(), Some("bob jones"));
+    /// assert_eq!(author.avatar(), None);
+    /// # }
+    /// ```
+    pub fn builder() -> AuthorBuilder {
+        AuthorBuilder::new()
+    }
+
+    /// An author with no name, url or avatar
+    #[deprecated(since = "0.3.0", note = "build authors with `Author::builder()`")]
     pub fn new() -> Author {
-        Author {
-            name: None,
-            url: None,
-            avatar: None,
-        }
+        Author::default()
     }
 
-    pub fn name<I: Into<String>>(mut self, name: I) -> Self {
-        self.name = Some(name.into());
-        self
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|s| &s[..])
     }
 
-    pub fn url<I: Into<String>>(mut self, url: I) -> Self {
-        self.url = Some(url.into());
-        self
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_ref().map(|s| &s[..])
     }
 
-    pub fn avatar<I: Into<String>>(mut self, avatar: I) -> Self {
-        self.avatar = Some(avatar.into());
-        self
+    pub fn avatar(&self) -> Option<&str> {
+        self.avatar.as_ref().map(|s| &s[..])
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
+impl<'de> Deserialize<'de> for Author {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        AuthorRef::deserialize(deserializer).map(AuthorRef::into_owned)
     }
 }
 
 /// Represents a `hub` for a feed
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Hub {
     #[serde(rename = "type")]
-    type_: String,
-    url: String,
+    pub(crate) type_: String,
+    pub(crate) url: String,
+    #[serde(flatten)]
+    pub(crate) extensions: Extensions,
+}
+
+impl_extensions!(Hub);
+
+impl Hub {
+    /// Creates a hub, e.g. `Hub::new("WebSub", "https://example.com/hub")`
+    pub fn new<T: Into<String>, U: Into<String>>(type_: T, url: U) -> Hub {
+        Hub {
+            type_: type_.into(),
+            url: url.into(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    /// The protocol used to talk to the hub, e.g. `WebSub`
+    pub fn hub_type(&self) -> &str {
+        &self.type_
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
+impl<'de> Deserialize<'de> for Hub {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        HubRef::deserialize(deserializer).map(HubRef::into_owned)
+    }
 }
 
 #[cfg(test)]
@@ -152,7 +434,7 @@ mod tests {
     #[test]
     fn serialize_feed() {
         let feed = Feed {
-            version: "https://jsonfeed.org/version/1".to_string(),
+            version: Version::V1,
             title: "some title".to_string(),
             items: vec![],
             home_page_url: None,
@@ -171,7 +453,7 @@ mod tests {
         let json = r#"{"version":"https://jsonfeed.org/version/1","title":"some title","items":[]}"#;
         let feed: Feed = serde_json::from_str(&json).unwrap();
         let expected = Feed {
-            version: "https://jsonfeed.org/version/1".to_string(),
+            version: Version::V1,
              title: "some title".to_string(),
              items: vec![],
              ..Default::default()
@@ -182,6 +464,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_feed_1_1() {
+        let feed = Feed {
+            version: Version::V1_1,
+            title: "some title".to_string(),
+            authors: Some(vec![Author::builder().name("bob jones").build()]),
+            language: Some("en-US".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&feed).unwrap(),
+            r#"{"version":"https://jsonfeed.org/version/1.1","title":"some title","items":[],"authors":[{"name":"bob jones"}],"language":"en-US"}"#
+        );
+    }
+
+    #[test]
+    fn deserialize_feed_1_1() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1.1","title":"some title","authors":[{"name":"bob jones"}],"language":"en-US","items":[]}"#;
+        let feed: Feed = serde_json::from_str(&json).unwrap();
+        let expected = Feed {
+            version: Version::V1_1,
+            title: "some title".to_string(),
+            authors: Some(vec![Author::builder().name("bob jones").build()]),
+            language: Some("en-US".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            feed,
+            expected
+        );
+    }
+
+    #[test]
+    fn upgrade_to_1_1() {
+        let mut feed = Feed {
+            author: Some(Author::builder().name("bob jones").build()),
+            items: vec![
+                Item {
+                    id: "1".to_string(),
+                    author: Some(Author::builder().name("jane doe").build()),
+                    ..Default::default()
+                },
+                Item {
+                    id: "2".to_string(),
+                    ..Default::default()
+                },
+                Item {
+                    id: "3".to_string(),
+                    author: Some(Author::builder().name("jane doe").build()),
+                    authors: Some(vec![Author::builder().name("jane doe").build()]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let report = feed.upgrade_to_1_1();
+        assert_eq!(
+            report,
+            UpgradeReport {
+                previous_version: Version::V1,
+                feed_author_moved: true,
+                feed_author_deduplicated: false,
+                items_upgraded: vec!["1".to_string()],
+                items_deduplicated: vec!["3".to_string()],
+            }
+        );
+        assert_eq!(feed.version, Version::V1_1);
+        assert_eq!(feed.author, None);
+        assert_eq!(feed.authors, Some(vec![Author::builder().name("bob jones").build()]));
+        assert_eq!(feed.items[0].author, None);
+        assert_eq!(feed.items[0].authors, Some(vec![Author::builder().name("jane doe").build()]));
+        assert_eq!(feed.items[1].authors, None);
+        assert_eq!(feed.items[2].author, None);
+        assert_eq!(feed.items[2].authors, Some(vec![Author::builder().name("jane doe").build()]));
+        assert!(feed.upgrade_to_1_1().is_empty());
+    }
+
     #[test]
     fn serialize_attachment() {
         let attachment = Attachment {
@@ -190,6 +549,7 @@ mod tests {
             title: Some("some title".to_string()),
             size_in_bytes: Some(1),
             duration_in_seconds: Some(1),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&attachment).unwrap(),
@@ -207,6 +567,7 @@ mod tests {
             title: Some("some title".to_string()),
             size_in_bytes: Some(1),
             duration_in_seconds: Some(1),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             attachment,
@@ -220,6 +581,7 @@ mod tests {
             name: Some("bob jones".to_string()),
             url: Some("http://example.com".to_string()),
             avatar: Some("http://img.com/blah".to_string()),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&author).unwrap(),
@@ -235,6 +597,7 @@ mod tests {
             name: Some("bob jones".to_string()),
             url: Some("http://example.com".to_string()),
             avatar: Some("http://img.com/blah".to_string()),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             author,
@@ -242,11 +605,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn feed_extensions_round_trip() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"some title","items":[],"_ourapp":{"about":"https://example.com","id":5},"unknown":true}"#;
+        let feed: Feed = serde_json::from_str(&json).unwrap();
+        assert_eq!(feed.extensions.len(), 1);
+        assert_eq!(feed.extensions["_ourapp"]["id"], 5);
+        assert_eq!(
+            serde_json::to_string(&feed).unwrap(),
+            r#"{"version":"https://jsonfeed.org/version/1","title":"some title","items":[],"_ourapp":{"about":"https://example.com","id":5}}"#
+        );
+    }
+
+    #[test]
+    fn typed_extensions() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct OurApp {
+            id: u64,
+        }
+
+        let mut author = Author::builder().build();
+        assert!(author.extension::<OurApp>("_ourapp").is_none());
+        assert!(author.set_extension("ourapp", OurApp { id: 1 }).is_err());
+        assert_eq!(author.set_extension("_ourapp", OurApp { id: 1 }).unwrap(), None);
+        assert_eq!(author.extension::<OurApp>("_ourapp").unwrap().unwrap(), OurApp { id: 1 });
+        assert!(author.extension::<String>("_ourapp").unwrap().is_err());
+        assert_eq!(
+            serde_json::to_string(&author).unwrap(),
+            r#"{"_ourapp":{"id":1}}"#
+        );
+        assert!(author.remove_extension("_ourapp").is_some());
+    }
+
+    #[test]
+    fn attachment_extensions_round_trip() {
+        let json = r#"{"url":"http://example.com","mime_type":"audio/mpeg","title":null,"size_in_bytes":null,"duration_in_seconds":null,"_podcast":{"chapters":[1,2]}}"#;
+        let attachment: Attachment = serde_json::from_str(&json).unwrap();
+        assert_eq!(attachment.extensions["_podcast"]["chapters"][1], 2);
+        assert_eq!(serde_json::to_string(&attachment).unwrap(), json);
+    }
+
     #[test]
     fn serialize_hub() {
         let hub = Hub {
             type_: "some-type".to_string(),
             url: "http://example.com".to_string(),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&hub).unwrap(),
@@ -254,6 +658,41 @@ mod tests {
         )
     }
 
+    #[test]
+    fn attachment_accessors() {
+        let attachment = Attachment::builder("http://example.com", "application/json")
+            .title("some title")
+            .size_in_bytes(1)
+            .duration_in_seconds(2)
+            .build();
+        assert_eq!(attachment.url(), "http://example.com");
+        assert_eq!(attachment.mime_type(), "application/json");
+        assert_eq!(attachment.title(), Some("some title"));
+        assert_eq!(attachment.size_in_bytes(), Some(1));
+        assert_eq!(attachment.duration_in_seconds(), Some(2));
+        assert!(attachment.extensions().is_empty());
+    }
+
+    #[test]
+    fn author_accessors() {
+        let author = Author::builder()
+            .name("bob jones")
+            .url("http://example.com")
+            .avatar("http://img.com/blah")
+            .build();
+        assert_eq!(author.name(), Some("bob jones"));
+        assert_eq!(author.url(), Some("http://example.com"));
+        assert_eq!(author.avatar(), Some("http://img.com/blah"));
+        assert!(author.extensions().is_empty());
+    }
+
+    #[test]
+    fn hub_accessors() {
+        let hub = Hub::new("WebSub", "http://example.com/hub");
+        assert_eq!(hub.hub_type(), "WebSub");
+        assert_eq!(hub.url(), "http://example.com/hub");
+    }
+
     #[test]
     fn deserialize_hub() {
         let json = r#"{"type":"some-type","url":"http://example.com"}"#;
@@ -261,6 +700,7 @@ mod tests {
         let expected = Hub {
             type_: "some-type".to_string(),
             url: "http://example.com".to_string(),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             hub,
@@ -268,6 +708,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hub_extensions() {
+        let json = r#"{"type":"WebSub","url":"http://example.com/hub","_lease":{"seconds":3600}}"#;
+        let hub: Hub = serde_json::from_str(json).unwrap();
+        assert_eq!(hub.extensions()["_lease"]["seconds"], 3600);
+        assert_eq!(serde_json::to_string(&hub).unwrap(), json);
+    }
+
     #[test]
     fn deser_podcast() {
         let json = r#"{
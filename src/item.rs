@@ -1,20 +1,150 @@
-use std::fmt;
+use std::convert::TryFrom;
 use std::default::Default;
 
 use feed::{Author, Attachment};
-use builder::ItemBuilder;
+use builder::{ItemBuilder, TypedItemBuilder, NoId, NoContent};
+use extensions::Extensions;
+use borrowed::ItemRef;
+use errors::{self, Error, missing_field_error};
 
-use serde::ser::{Serialize, Serializer, SerializeStruct};
-use serde::de::{self, Deserialize, Deserializer, Visitor, MapAccess};
+use serde::ser::{Serialize, Serializer, SerializeMap};
+use serde::de::{Deserialize, Deserializer};
 
 /// Represents the `content_html` and `content_text` attributes of an item
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+///
+/// On its own, `Content` (de)serializes as an object with the
+/// `content_html` and/or `content_text` keys, the same way it appears in
+/// an item. Absent variants are omitted rather than written as `null`.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Content {
     Html(String),
     Text(String),
     Both(String, String),
 }
 
+impl Content {
+    /// Content with only `content_html`
+    pub fn from_html<S: Into<String>>(html: S) -> Content {
+        Content::Html(html.into())
+    }
+
+    /// Content with only `content_text`
+    pub fn from_text<S: Into<String>>(text: S) -> Content {
+        Content::Text(text.into())
+    }
+
+    /// Content with both `content_html` and `content_text`
+    pub fn from_html_and_text<S: Into<String>, T: Into<String>>(html: S, text: T) -> Content {
+        Content::Both(html.into(), text.into())
+    }
+
+    /// Builds the content from optional `content_html` and `content_text`
+    /// values, returning `None` if both are absent
+    pub fn from_parts(html: Option<String>, text: Option<String>) -> Option<Content> {
+        match (html, text) {
+            (Some(s), Some(t)) => Some(Content::Both(s, t)),
+            (Some(s), None) => Some(Content::Html(s)),
+            (None, Some(t)) => Some(Content::Text(t)),
+            (None, None) => None,
+        }
+    }
+
+    /// The `content_html` value
+    pub fn html(&self) -> Option<&str> {
+        match *self {
+            Content::Html(ref s) | Content::Both(ref s, _) => Some(s),
+            Content::Text(_) => None,
+        }
+    }
+
+    /// The `content_text` value
+    pub fn text(&self) -> Option<&str> {
+        match *self {
+            Content::Text(ref t) | Content::Both(_, ref t) => Some(t),
+            Content::Html(_) => None,
+        }
+    }
+
+    /// Sets `content_html`, keeping any `content_text`
+    pub fn with_html<S: Into<String>>(self, html: S) -> Content {
+        match self {
+            Content::Text(t) | Content::Both(_, t) => Content::Both(html.into(), t),
+            Content::Html(_) => Content::Html(html.into()),
+        }
+    }
+
+    /// Sets `content_text`, keeping any `content_html`
+    pub fn with_text<S: Into<String>>(self, text: S) -> Content {
+        match self {
+            Content::Html(s) | Content::Both(s, _) => Content::Both(s, text.into()),
+            Content::Text(_) => Content::Text(text.into()),
+        }
+    }
+}
+
+impl From<Content> for (Option<String>, Option<String>) {
+    /// Splits the content into its `content_html` and `content_text` values
+    fn from(content: Content) -> (Option<String>, Option<String>) {
+        match content {
+            Content::Html(s) => (Some(s), None),
+            Content::Text(t) => (None, Some(t)),
+            Content::Both(s, t) => (Some(s), Some(t)),
+        }
+    }
+}
+
+impl TryFrom<(Option<String>, Option<String>)> for Content {
+    type Error = Error;
+
+    /// Joins `content_html` and `content_text` values, failing with
+    /// `Error::MissingField` if both are absent
+    fn try_from(parts: (Option<String>, Option<String>)) -> errors::Result<Content> {
+        Content::from_parts(parts.0, parts.1)
+            .ok_or_else(|| Error::missing_field("content_html or content_text"))
+    }
+}
+
+impl Serialize for Content {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        let mut state = serializer.serialize_map(None)?;
+        self.serialize_entries(&mut state)?;
+        state.end()
+    }
+}
+
+impl Content {
+    fn serialize_entries<M: SerializeMap>(&self, state: &mut M) -> Result<(), M::Error> {
+        if let Some(s) = self.html() {
+            state.serialize_entry("content_html", s)?;
+        }
+        if let Some(t) = self.text() {
+            state.serialize_entry("content_text", t)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D>(deserializer: D) -> Result<Content, D::Error>
+        where D: Deserializer<'de>
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Parts {
+            #[serde(default)]
+            content_html: Option<String>,
+            #[serde(default)]
+            content_text: Option<String>,
+        }
+
+        let parts = Parts::deserialize(deserializer)?;
+        Content::from_parts(parts.content_html, parts.content_text)
+            .ok_or_else(|| missing_field_error("content_html or content_text"))
+    }
+}
+
 /// Represents an item in a feed
 #[derive(Debug, Clone, PartialEq)]
 pub struct Item {
@@ -26,19 +156,32 @@ pub struct Item {
     pub summary: Option<String>,
     pub image: Option<String>,
     pub banner_image: Option<String>,
-    pub date_published: Option<String>, // todo DateTime objects?
+    /// An RFC 3339 timestamp, e.g. `2017-05-17T10:02:12-04:00`
+    pub date_published: Option<String>,
+    /// An RFC 3339 timestamp, e.g. `2017-05-17T10:02:12-04:00`
     pub date_modified: Option<String>,
     pub author: Option<Author>,
+    pub authors: Option<Vec<Author>>,
     pub tags: Option<Vec<String>>,
+    pub language: Option<String>,
     pub attachments: Option<Vec<Attachment>>,
+    pub extensions: Extensions,
 }
 
 impl Item {
     pub fn builder() -> ItemBuilder {
         ItemBuilder::new()
     }
+
+    /// Like `builder()`, but checks at compile time that an id and
+    /// content are set
+    pub fn typed_builder() -> TypedItemBuilder<NoId, NoContent> {
+        TypedItemBuilder::new()
+    }
 }
 
+impl_extensions!(Item);
+
 impl Default for Item {
     fn default() -> Item {
         Item {
@@ -53,293 +196,94 @@ impl Default for Item {
             date_published: None,
             date_modified: None,
             author: None,
+            authors: None,
             tags: None,
+            language: None,
             attachments: None,
+            extensions: Extensions::new(),
         }
     }
 }
 
+/// The keys an item may contain, besides extensions
+pub(crate) const FIELDS: &[&str] = &[
+    "id",
+    "url",
+    "external_url",
+    "title",
+    "content_html",
+    "content_text",
+    "summary",
+    "image",
+    "banner_image",
+    "date_published",
+    "date_modified",
+    "author",
+    "authors",
+    "tags",
+    "language",
+    "attachments",
+];
+
 impl Serialize for Item {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where S: Serializer
     {
-        let mut state = serializer.serialize_struct("Item", 14)?;
-        state.serialize_field("id", &self.id)?;
+        let mut state = serializer.serialize_map(None)?;
+        state.serialize_entry("id", &self.id)?;
         if self.url.is_some() {
-            state.serialize_field("url", &self.url)?;
+            state.serialize_entry("url", &self.url)?;
         }
         if self.external_url.is_some() {
-            state.serialize_field("external_url", &self.external_url)?;
+            state.serialize_entry("external_url", &self.external_url)?;
         }
         if self.title.is_some() {
-            state.serialize_field("title", &self.title)?;
+            state.serialize_entry("title", &self.title)?;
         }
-        match self.content {
-            Content::Html(ref s) => {
-                state.serialize_field("content_html", s)?;
-                state.serialize_field("content_text", &None::<Option<&str>>)?;
-            },
-            Content::Text(ref s) => {
-                state.serialize_field("content_html", &None::<Option<&str>>)?;
-                state.serialize_field("content_text", s)?;
-            },
-            Content::Both(ref s, ref t) => {
-                state.serialize_field("content_html", s)?;
-                state.serialize_field("content_text", t)?;
-            },
-        };
+        self.content.serialize_entries(&mut state)?;
         if self.summary.is_some() {
-            state.serialize_field("summary", &self.summary)?;
+            state.serialize_entry("summary", &self.summary)?;
         }
         if self.image.is_some() {
-            state.serialize_field("image", &self.image)?;
+            state.serialize_entry("image", &self.image)?;
         }
         if self.banner_image.is_some() {
-            state.serialize_field("banner_image", &self.banner_image)?;
+            state.serialize_entry("banner_image", &self.banner_image)?;
         }
         if self.date_published.is_some() {
-            state.serialize_field("date_published", &self.date_published)?;
+            state.serialize_entry("date_published", &self.date_published)?;
         }
         if self.date_modified.is_some() {
-            state.serialize_field("date_modified", &self.date_modified)?;
+            state.serialize_entry("date_modified", &self.date_modified)?;
         }
         if self.author.is_some() {
-            state.serialize_field("author", &self.author)?;
+            state.serialize_entry("author", &self.author)?;
+        }
+        if self.authors.is_some() {
+            state.serialize_entry("authors", &self.authors)?;
         }
         if self.tags.is_some() {
-            state.serialize_field("tags", &self.tags)?;
+            state.serialize_entry("tags", &self.tags)?;
+        }
+        if self.language.is_some() {
+            state.serialize_entry("language", &self.language)?;
         }
         if self.attachments.is_some() {
-            state.serialize_field("attachments", &self.attachments)?;
+            state.serialize_entry("attachments", &self.attachments)?;
+        }
+        for (key, value) in &self.extensions {
+            state.serialize_entry(key, value)?;
         }
         state.end()
     }
 }
 
 impl<'de> Deserialize<'de> for Item {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> 
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where D: Deserializer<'de>
     {
-        enum Field {
-            Id,
-            Url,
-            ExternalUrl,
-            Title,
-            ContentHtml,
-            ContentText,
-            Summary,
-            Image,
-            BannerImage,
-            DatePublished,
-            DateModified,
-            Author,
-            Tags,
-            Attachments,
-        };
-
-        impl<'de> Deserialize<'de> for Field {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-                where D: Deserializer<'de>
-            {
-                struct FieldVisitor;
-
-                impl<'de> Visitor<'de> for FieldVisitor {
-                    type Value = Field;
-
-                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("non-expected field")
-                    }
-
-                    fn visit_str<E>(self, value: &str) -> Result<Field, E>
-                        where E: de::Error
-                    {
-                        match value {
-                            "id" => Ok(Field::Id),
-                            "url" => Ok(Field::Url),
-                            "external_url" => Ok(Field::ExternalUrl),
-                            "title" => Ok(Field::Title),
-                            "content_html" => Ok(Field::ContentHtml),
-                            "content_text" => Ok(Field::ContentText),
-                            "summary" => Ok(Field::Summary),
-                            "image" => Ok(Field::Image),
-                            "banner_image" => Ok(Field::BannerImage),
-                            "date_published" => Ok(Field::DatePublished),
-                            "date_modified" => Ok(Field::DateModified),
-                            "author" => Ok(Field::Author),
-                            "tags" => Ok(Field::Tags),
-                            "attachments" => Ok(Field::Attachments),
-                            _ => Err(de::Error::unknown_field(value, FIELDS)),
-                        }
-                    }
-                }
-
-                deserializer.deserialize_identifier(FieldVisitor)
-            }
-        }
-
-        struct ItemVisitor;
-
-        impl<'de> Visitor<'de> for ItemVisitor {
-            type Value = Item;
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("non-expected thing")
-            }
-
-            fn visit_map<V>(self, mut map: V) -> Result<Item, V::Error>
-                where V: MapAccess<'de>
-            {
-                let mut id = None;
-                let mut url = None;
-                let mut external_url = None;
-                let mut title = None;
-                let mut content_html: Option<String> = None;
-                let mut content_text: Option<String> = None;
-                let mut summary = None;
-                let mut image = None;
-                let mut banner_image = None;
-                let mut date_published = None;
-                let mut date_modified = None;
-                let mut author = None;
-                let mut tags = None;
-                let mut attachments = None;
-
-                while let Some(key) = map.next_key()? {
-                    match key {
-                        Field::Id => {
-                            if id.is_some() {
-                                return Err(de::Error::duplicate_field("id"));
-                            }
-                            id = Some(map.next_value()?);
-                        },
-                        Field::Url => {
-                            if url.is_some() {
-                                return Err(de::Error::duplicate_field("url"));
-                            }
-                            url = map.next_value()?;
-                        },
-                        Field::ExternalUrl => {
-                            if external_url.is_some() {
-                                return Err(de::Error::duplicate_field("external_url"));
-                            }
-                            external_url = map.next_value()?;
-                        },
-                        Field::Title => {
-                            if title.is_some() {
-                                return Err(de::Error::duplicate_field("title"));
-                            }
-                            title = map.next_value()?;
-                        },
-                        Field::ContentHtml => {
-                            if content_html.is_some() {
-                                return Err(de::Error::duplicate_field("content_html"));
-                            }
-                            content_html = map.next_value()?;
-                        },
-                        Field::ContentText => {
-                            if content_text.is_some() {
-                                return Err(de::Error::duplicate_field("content_text"));
-                            }
-                            content_text = map.next_value()?;
-                        },
-                        Field::Summary => {
-                            if summary.is_some() {
-                                return Err(de::Error::duplicate_field("summary"));
-                            }
-                            summary = map.next_value()?;
-                        },
-                        Field::Image => {
-                            if image.is_some() {
-                                return Err(de::Error::duplicate_field("image"));
-                            }
-                            image = map.next_value()?;
-                        },
-                        Field::BannerImage => {
-                            if banner_image.is_some() {
-                                return Err(de::Error::duplicate_field("banner_image"));
-                            }
-                            banner_image = map.next_value()?;
-                        },
-                        Field::DatePublished => {
-                            if date_published.is_some() {
-                                return Err(de::Error::duplicate_field("date_published"));
-                            }
-                            date_published = map.next_value()?;
-                        },
-                        Field::DateModified => {
-                            if date_modified.is_some() {
-                                return Err(de::Error::duplicate_field("date_modified"));
-                            }
-                            date_modified = map.next_value()?;
-                        },
-                        Field::Author => {
-                            if author.is_some() {
-                                return Err(de::Error::duplicate_field("author"));
-                            }
-                            author = map.next_value()?;
-                        },
-                        Field::Tags => {
-                            if tags.is_some() {
-                                return Err(de::Error::duplicate_field("tags"));
-                            }
-                            tags = map.next_value()?;
-                        },
-                        Field::Attachments => {
-                            if attachments.is_some() {
-                                return Err(de::Error::duplicate_field("attachments"));
-                            }
-                            attachments = map.next_value()?;
-                        },
-                    }
-                }
-
-                let id = id.ok_or_else(|| de::Error::missing_field("id"))?;
-                let content = match (content_html, content_text) {
-                    (Some(s), Some(t)) => {
-                        Content::Both(s.to_string(), t.to_string())
-                    },
-                    (Some(s), _) => {
-                        Content::Html(s.to_string())
-                    },
-                    (_, Some(t)) => {
-                        Content::Text(t.to_string())
-                    },
-                    _ => return Err(de::Error::missing_field("content_html or content_text")),
-                };
-
-                Ok(Item {
-                    id,
-                    url,
-                    external_url,
-                    title,
-                    content,
-                    summary,
-                    image,
-                    banner_image,
-                    date_published,
-                    date_modified,
-                    author,
-                    tags,
-                    attachments,
-                })
-            }
-        }
-
-        const FIELDS: &'static [&'static str] = &[
-            "id",
-            "url",
-            "external_url",
-            "title",
-            "content",
-            "summary",
-            "image",
-            "banner_image",
-            "date_published",
-            "date_modified",
-            "author",
-            "tags",
-            "attachments",
-        ];
-        deserializer.deserialize_struct("Item", FIELDS, ItemVisitor)
+        // shares its visitor with `ItemRef`
+        ItemRef::deserialize(deserializer).map(ItemRef::into_owned)
     }
 }
 
@@ -361,15 +305,18 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&item).unwrap(),
-            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":null,"summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
+            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
         );
     }
 
@@ -385,15 +332,18 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&item).unwrap(),
-            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":null,"content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
+            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
         );
     }
 
@@ -409,22 +359,25 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
         };
         assert_eq!(
             serde_json::to_string(&item).unwrap(),
-            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
+            r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#
         );
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn deserialize_item__content_html() {
-        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":null,"summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
+        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":null,"summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
         let item: Item = serde_json::from_str(&json).unwrap();
         let expected = Item {
             id: "1".into(),
@@ -435,11 +388,14 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
         };
         assert_eq!(item, expected);
     }
@@ -447,7 +403,7 @@ mod tests {
     #[test]
     #[allow(non_snake_case)]
     fn deserialize_item__content_text() {
-        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":null,"content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
+        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":null,"content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
         let item: Item = serde_json::from_str(&json).unwrap();
         let expected = Item {
             id: "1".into(),
@@ -458,11 +414,14 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
         };
         assert_eq!(item, expected);
     }
@@ -470,7 +429,7 @@ mod tests {
     #[test]
     #[allow(non_snake_case)]
     fn deserialize_item__content_both() {
-        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01 10:00:00","date_modified":"2017-01-01 10:00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
+        let json = r#"{"id":"1","url":"http://example.com/feed.json","external_url":"http://example.com/feed.json","title":"feed title","content_html":"<p>content</p>","content_text":"content","summary":"feed summary","image":"http://img.com/blah","banner_image":"http://img.com/blah","date_published":"2017-01-01T10:00:00+00:00","date_modified":"2017-01-01T10:00:00+00:00","author":{"name":"bob jones","url":"http://example.com","avatar":"http://img.com/blah"},"tags":["json","feed"],"attachments":[]}"#;
         let item: Item = serde_json::from_str(&json).unwrap();
         let expected = Item {
             id: "1".into(),
@@ -481,13 +440,96 @@ mod tests {
             summary: Some("feed summary".into()),
             image: Some("http://img.com/blah".into()),
             banner_image: Some("http://img.com/blah".into()),
-            date_published: Some("2017-01-01 10:00:00".into()),
-            date_modified: Some("2017-01-01 10:00:00".into()),
-            author: Some(Author::new().name("bob jones").url("http://example.com").avatar("http://img.com/blah")),
+            date_published: Some("2017-01-01T10:00:00+00:00".into()),
+            date_modified: Some("2017-01-01T10:00:00+00:00".into()),
+            author: Some(Author::builder().name("bob jones").url("http://example.com").avatar("http://img.com/blah").build()),
+            authors: None,
             tags: Some(vec!["json".into(), "feed".into()]),
+            language: None,
             attachments: Some(vec![]),
+            extensions: Extensions::new(),
+        };
+        assert_eq!(item, expected);
+    }
+
+    #[test]
+    fn deserialize_item_1_1() {
+        let json = r#"{"id":"1","content_text":"content","authors":[{"name":"bob jones"}],"language":"en-US"}"#;
+        let item: Item = serde_json::from_str(&json).unwrap();
+        let expected = Item {
+            id: "1".into(),
+            content: Content::Text("content".into()),
+            authors: Some(vec![Author::builder().name("bob jones").build()]),
+            language: Some("en-US".into()),
+            ..Default::default()
         };
         assert_eq!(item, expected);
     }
+
+    #[test]
+    fn item_extensions_round_trip() {
+        let json = r#"{"id":"1","content_text":"content","_ourapp":{"score":3}}"#;
+        let item: Item = serde_json::from_str(&json).unwrap();
+        assert_eq!(item.extension::<::serde_json::Value>("_ourapp").unwrap().unwrap()["score"], 3);
+        assert_eq!(serde_json::to_string(&item).unwrap(), r#"{"id":"1","content_text":"content","_ourapp":{"score":3}}"#);
+    }
+
+    #[test]
+    fn item_unknown_field() {
+        let json = r#"{"id":"1","content_text":"content","ourapp":{"score":3}}"#;
+        assert!(serde_json::from_str::<Item>(&json).is_err());
+    }
+
+    #[test]
+    fn deserialize_item_dates() {
+        let json = r#"{"id":"1","content_text":"","date_published":"2017-04-26T01:09:45+02:00"}"#;
+        let item: Item = serde_json::from_str(&json).unwrap();
+        assert_eq!(item.date_published, Some("2017-04-26T01:09:45+02:00".to_string()));
+    }
+
+    #[test]
+    fn deserialize_item_unchecked_date() {
+        // checking dates is up to `ParseOptions::check_dates`
+        let json = r#"{"date_modified":"Tue, 10 Jun 2003 04:00:00 GMT","id":"1","content_text":""}"#;
+        let item = serde_json::from_str::<Item>(json).unwrap();
+        assert_eq!(item.date_modified, Some("Tue, 10 Jun 2003 04:00:00 GMT".to_string()));
+    }
+
+    #[test]
+    fn content_accessors() {
+        let content = Content::Text("text".into());
+        assert_eq!(content.html(), None);
+        assert_eq!(content.text(), Some("text"));
+        let content = content.with_html("<p>html</p>");
+        assert_eq!(content, Content::Both("<p>html</p>".into(), "text".into()));
+        assert_eq!(content.clone().with_text("new"), Content::Both("<p>html</p>".into(), "new".into()));
+        let parts: (Option<String>, Option<String>) = content.into();
+        assert_eq!(parts, (Some("<p>html</p>".into()), Some("text".into())));
+        assert_eq!(Content::from_parts(None, Some("text".into())), Some(Content::Text("text".into())));
+        assert_eq!(Content::from_parts(None, None), None);
+    }
+
+    #[test]
+    fn content_constructors() {
+        assert_eq!(Content::from_html("<p>html</p>"), Content::Html("<p>html</p>".into()));
+        assert_eq!(Content::from_text("text"), Content::Text("text".into()));
+        assert_eq!(Content::from_html_and_text("<p>html</p>", "text"),
+                   Content::Both("<p>html</p>".into(), "text".into()));
+        let content = Content::try_from((Some("<p>html</p>".to_string()), None)).unwrap();
+        assert_eq!(content, Content::Html("<p>html</p>".into()));
+        match Content::try_from((None, None)) {
+            Err(Error::MissingField { field, .. }) => assert_eq!(field, "content_html or content_text"),
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn content_standalone() {
+        let content = Content::Html("<p>html</p>".into());
+        assert_eq!(serde_json::to_string(&content).unwrap(), r#"{"content_html":"<p>html</p>"}"#);
+        let content: Content = serde_json::from_str(r#"{"content_html":"<p>html</p>","content_text":null}"#).unwrap();
+        assert_eq!(content, Content::Html("<p>html</p>".into()));
+        assert!(serde_json::from_str::<Content>("{}").is_err());
+    }
 }
 
@@ -7,7 +7,7 @@
 //!
 //! Example:
 //!
-//! ```ignore
+//! ```rust
 //! extern crate jsonfeed;
 //!
 //! use jsonfeed::{Feed, Item};
@@ -19,39 +19,77 @@
 //!         "items": []
 //!     }"#;
 //!     let feed = jsonfeed::from_str(j).unwrap();
+//!     assert_eq!(feed.title, "my feed");
 //!
 //!     let new_feed = Feed::builder()
 //!                         .title("some other feed")
 //!                         .item(Item::builder()
+//!                                 .id("1")
 //!                                 .title("some item title")
 //!                                 .content_html("<p>Hello, World</p>")
 //!                                 .build()?)
 //!                         .item(Item::builder()
+//!                                 .id("2")
 //!                                 .title("some other item title")
 //!                                 .content_text("Hello, World!")
 //!                                 .build()?)
-//!                         .build();
+//!                         .build()?;
 //!     println!("{}", jsonfeed::to_string(&new_feed).unwrap());
 //!     Ok(())
 //! }
 //! fn main() {
-//!     let _ = run();
+//!     run().unwrap();
 //! }
 //! ```
 
 extern crate serde;
-#[macro_use] extern crate error_chain;
 #[macro_use] extern crate serde_derive;
 extern crate serde_json;
+extern crate serde_path_to_error;
+#[cfg(feature = "chrono")] extern crate chrono;
+#[cfg(feature = "url")] extern crate url;
+#[cfg(feature = "xml-rs")] extern crate xml;
 
 mod errors;
+#[macro_use] mod extensions;
 mod item;
 mod feed;
 mod builder;
+mod version;
+mod parse;
+mod date;
+mod urls;
+mod validate;
+mod convert;
+mod pagination;
+mod merge;
+mod diff;
+mod query;
+pub mod stream;
+pub mod borrowed;
+#[cfg(feature = "xml-rs")] mod dom;
+#[cfg(feature = "rss")] pub mod rss;
+#[cfg(feature = "atom")] pub mod atom;
+#[cfg(feature = "opml")] pub mod opml;
 
 pub use errors::*;
 pub use item::*;
-pub use feed::{Feed, Author, Attachment};
+pub use feed::{Feed, Author, Attachment, Hub, UpgradeReport};
+pub use builder::{Builder, ItemBuilder, AttachmentBuilder, AuthorBuilder};
+pub use builder::{TypedItemBuilder, NoId, HasId, NoContent, HasContent};
+pub use version::{Version, VERSION_1, VERSION_1_1};
+pub use extensions::Extensions;
+pub use date::IntoDate;
+#[cfg(feature = "url")] pub use url::Url;
+pub use validate::{Diagnostic, Severity};
+pub use convert::{ConversionReport, ConversionWarning};
+pub use pagination::{Fetcher, Paginator, PaginatedItems};
+pub use merge::{merge, MergeOptions};
+pub use diff::{FeedDiff, ItemDiff};
+pub use query::Query;
+pub use stream::FeedWriter;
+pub use borrowed::{FeedRef, ItemRef};
+pub use parse::{ParseOptions, ParseReport, ParseWarning, from_str_with, from_reader_with};
 
 use std::io::Write;
 
@@ -71,46 +109,59 @@ use std::io::Write;
 /// # }
 /// ```
 pub fn from_str(s: &str) -> Result<Feed> {
-    Ok(serde_json::from_str(s)?)
+    let feed: Feed = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(s))?;
+    check(&feed)?;
+    Ok(feed)
 }
 
 /// Deserialize a Feed object from an IO stream of JSON
 pub fn from_reader<R: ::std::io::Read>(r: R) -> Result<Feed> {
-    Ok(serde_json::from_reader(r)?)
+    let feed: Feed = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_reader(r))?;
+    check(&feed)?;
+    Ok(feed)
 }
 
 /// Deserialize a Feed object from bytes of JSON text
 pub fn from_slice<'a>(v: &'a [u8]) -> Result<Feed> {
-    Ok(serde_json::from_slice(v)?)
+    let feed: Feed = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(v))?;
+    check(&feed)?;
+    Ok(feed)
 }
 
 /// Convert a serde_json::Value type to a Feed object
 pub fn from_value(value: serde_json::Value) -> Result<Feed> {
-    Ok(serde_json::from_value(value)?)
+    let feed: Feed = serde_path_to_error::deserialize(value)?;
+    check(&feed)?;
+    Ok(feed)
 }
 
 /// Serialize a Feed to a JSON Feed string
 pub fn to_string(value: &Feed) -> Result<String> {
+    check(value)?;
     Ok(serde_json::to_string(value)?)
 }
 
 /// Pretty-print a Feed to a JSON Feed string
 pub fn to_string_pretty(value: &Feed) -> Result<String> {
+    check(value)?;
     Ok(serde_json::to_string_pretty(value)?)
 }
 
 /// Convert a Feed to a serde_json::Value
 pub fn to_value(value: Feed) -> Result<serde_json::Value> {
+    check(&value)?;
     Ok(serde_json::to_value(value)?)
 }
 
 /// Convert a Feed to a vector of bytes of JSON
 pub fn to_vec(value: &Feed) -> Result<Vec<u8>> {
+    check(value)?;
     Ok(serde_json::to_vec(value)?)
 }
 
 /// Convert a Feed to a vector of bytes of pretty-printed JSON
 pub fn to_vec_pretty(value: &Feed) -> Result<Vec<u8>> {
+    check(value)?;
     Ok(serde_json::to_vec_pretty(value)?)
 }
 
@@ -118,6 +169,7 @@ pub fn to_vec_pretty(value: &Feed) -> Result<Vec<u8>> {
 pub fn to_writer<W>(writer: W, value: &Feed) -> Result<()>
         where W: Write
 {
+    check(value)?;
     Ok(serde_json::to_writer(writer, value)?)
 }
 
@@ -125,9 +177,17 @@ pub fn to_writer<W>(writer: W, value: &Feed) -> Result<()>
 pub fn to_writer_pretty<W>(writer: W, value: &Feed) -> Result<()>
         where W: Write
 {
+    check(value)?;
     Ok(serde_json::to_writer_pretty(writer, value)?)
 }
 
+fn check(feed: &Feed) -> Result<()> {
+    if !feed.version.is_known() {
+        return Err(Error::UnsupportedVersion { version: feed.version.to_string() });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +234,25 @@ mod tests {
         );
     }
     #[test]
+    fn from_str_unsupported_version() {
+        let feed = r#"{"version": "https://jsonfeed.org/version/2","title":"","items":[]}"#;
+        match super::from_str(&feed) {
+            Err(Error::UnsupportedVersion { version }) => {
+                assert_eq!(version, "https://jsonfeed.org/version/2");
+            },
+            other => panic!("expected an unsupported version error, got {:?}", other),
+        }
+    }
+    #[test]
+    fn from_str_1_1() {
+        let feed = r#"{"version": "https://jsonfeed.org/version/1.1","title":"","items":[]}"#;
+        let expected = Feed { version: Version::V1_1, ..Feed::default() };
+        assert_eq!(
+                super::from_str(&feed).unwrap(),
+                expected
+        );
+    }
+    #[test]
     fn to_string() {
         let feed = Feed::default();
         let expected = r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[]}"#;
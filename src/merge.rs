@@ -0,0 +1,179 @@
+//! Combining several feeds into one
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use date;
+use feed::Feed;
+use item::Item;
+use version::Version;
+
+/// Options for `merge`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MergeOptions {
+    /// The title of the merged feed
+    pub title: String,
+    /// Also treat items as duplicates when they share a `url` or
+    /// `external_url`, not just an `id`
+    pub dedupe_by_url: bool,
+    /// Take at most this many items from each feed, newest first
+    pub max_items_per_feed: Option<usize>,
+}
+
+/// Merges feeds into one, newest items first
+///
+/// Items are deduplicated by id, and by URL if `dedupe_by_url` is set,
+/// keeping the first copy in the order the feeds are given. The cap on
+/// items per feed applies before deduplication. Items without an author
+/// are attributed to the author of the feed they came from. Items
+/// without a `date_published` come after the dated ones.
+///
+/// The merged feed is a version 1.1 feed if any of the feeds are, or if
+/// it gives items the `authors` of their feed. It has no metadata besides
+/// its title.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::{Feed, Item, MergeOptions};
+/// # fn main() {
+/// let a = Feed::builder().title("a")
+///     .item(Item::builder().id("1").content_text("").build().unwrap())
+///     .build().unwrap();
+/// let b = Feed::builder().title("b")
+///     .item(Item::builder().id("1").content_text("").build().unwrap())
+///     .item(Item::builder().id("2").content_text("").build().unwrap())
+///     .build().unwrap();
+/// let options = MergeOptions { title: "river".to_string(), ..MergeOptions::default() };
+/// let river = jsonfeed::merge(vec![a, b], options);
+/// assert_eq!(river.title, "river");
+/// assert_eq!(river.items.len(), 2);
+/// # }
+/// ```
+pub fn merge<I>(feeds: I, options: MergeOptions) -> Feed
+    where I: IntoIterator<Item = Feed>
+{
+    let mut merged = Feed {
+        title: options.title.clone(),
+        ..Feed::default()
+    };
+    let mut ids = HashSet::new();
+    let mut urls = HashSet::new();
+
+    for feed in feeds {
+        if feed.version == Version::V1_1 {
+            merged.version = Version::V1_1;
+        }
+        let mut items = feed.items;
+        newest_first(&mut items);
+        if let Some(max) = options.max_items_per_feed {
+            items.truncate(max);
+        }
+        for mut item in items {
+            if ids.contains(&item.id) {
+                continue;
+            }
+            if options.dedupe_by_url {
+                let item_urls: Vec<&String> = item.url.iter().chain(item.external_url.iter()).collect();
+                if item_urls.iter().any(|url| urls.contains(*url)) {
+                    continue;
+                }
+                urls.extend(item_urls.into_iter().cloned());
+            }
+            ids.insert(item.id.clone());
+            if item.author.is_none() && item.authors.is_none() {
+                item.author = feed.author.clone();
+                item.authors = feed.authors.clone();
+                // `authors` is new in 1.1
+                if item.authors.is_some() {
+                    merged.version = Version::V1_1;
+                }
+            }
+            merged.items.push(item);
+        }
+    }
+
+    newest_first(&mut merged.items);
+    merged
+}
+
+fn newest_first(items: &mut [Item]) {
+    items.sort_by_key(|item| Reverse(item.date_published.as_deref().and_then(date::timestamp)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feed::Author;
+
+    fn item(id: &str, url: Option<&str>, day: Option<u32>) -> Item {
+        let mut item = Item::builder().id(id).content_text(id).build().unwrap();
+        item.url = url.map(|url| url.to_string());
+        item.date_published = day.map(|day| format!("2017-01-0{}T00:00:00Z", day));
+        item
+    }
+
+    fn feed(author: &str, items: Vec<Item>) -> Feed {
+        Feed {
+            author: Some(Author::builder().name(author).build()),
+            items,
+            ..Feed::default()
+        }
+    }
+
+    fn ids(feed: &Feed) -> Vec<&str> {
+        feed.items.iter().map(|item| &item.id[..]).collect()
+    }
+
+    #[test]
+    fn merge_sorts_and_dedupes() {
+        let a = feed("alice", vec![item("a1", None, Some(1)), item("shared", None, Some(5))]);
+        let b = feed("bob", vec![item("shared", None, Some(6)), item("b1", None, None), item("b2", None, Some(3))]);
+        let merged = merge(vec![a, b], MergeOptions::default());
+        assert_eq!(ids(&merged), vec!["shared", "b2", "a1", "b1"]);
+        assert_eq!(merged.items[0].author, Some(Author::builder().name("alice").build()));
+        assert_eq!(merged.items[1].author, Some(Author::builder().name("bob").build()));
+    }
+
+    #[test]
+    fn merge_dedupes_by_url() {
+        let a = feed("alice", vec![item("a1", Some("https://example.com/post"), Some(1))]);
+        let mut b1 = item("b1", None, Some(2));
+        b1.external_url = Some("https://example.com/post".to_string());
+        let b = feed("bob", vec![b1, item("b2", None, Some(3))]);
+
+        assert_eq!(ids(&merge(vec![a.clone(), b.clone()], MergeOptions::default())), vec!["b2", "b1", "a1"]);
+        let options = MergeOptions { dedupe_by_url: true, ..MergeOptions::default() };
+        assert_eq!(ids(&merge(vec![a, b], options)), vec!["b2", "a1"]);
+    }
+
+    #[test]
+    fn merge_caps_items_per_feed() {
+        let a = feed("alice", vec![item("a1", None, Some(1)), item("a2", None, Some(2)), item("a3", None, Some(3))]);
+        let b = feed("bob", vec![item("b1", None, Some(4))]);
+        let options = MergeOptions { max_items_per_feed: Some(2), ..MergeOptions::default() };
+        assert_eq!(ids(&merge(vec![a, b], options)), vec!["b1", "a3", "a2"]);
+    }
+
+    #[test]
+    fn merge_keeps_item_authors() {
+        let mut with_author = item("a1", None, None);
+        with_author.authors = Some(vec![Author::builder().name("carol").build()]);
+        let a = Feed { version: Version::V1_1, ..feed("alice", vec![with_author]) };
+        let merged = merge(vec![a], MergeOptions::default());
+        assert_eq!(merged.version, Version::V1_1);
+        assert_eq!(merged.items[0].author, None);
+        assert_eq!(merged.items[0].authors, Some(vec![Author::builder().name("carol").build()]));
+    }
+
+    #[test]
+    fn merge_copies_feed_authors() {
+        let a = Feed { authors: Some(vec![Author::builder().name("dave").build()]), ..feed("alice", vec![item("a1", None, None)]) };
+        assert_eq!(a.version, Version::V1);
+        let merged = merge(vec![a], MergeOptions::default());
+        assert_eq!(merged.version, Version::V1_1);
+        assert_eq!(merged.items[0].authors, Some(vec![Author::builder().name("dave").build()]));
+
+        let b = feed("bob", vec![item("b1", None, None)]);
+        assert_eq!(merge(vec![b], MergeOptions::default()).version, Version::V1);
+    }
+}
@@ -0,0 +1,235 @@
+//! OPML 2.0 subscription lists
+//!
+//! Subscriptions are read from every `outline` with an `xmlUrl`, wherever
+//! it is in the document. The outlines around a subscription make up its
+//! category path. Writing nests the subscriptions the same way.
+
+use std::io::Read;
+
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+use errors::*;
+use dom::{self, Element};
+use feed::Feed;
+
+/// A feed in a subscription list
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Subscription {
+    pub title: String,
+    pub feed_url: String,
+    pub home_page_url: Option<String>,
+    /// The folders the subscription is filed under, outermost first
+    pub category: Vec<String>,
+}
+
+impl Subscription {
+    /// A subscription with no home page, outside any folder
+    pub fn new<T: Into<String>, U: Into<String>>(title: T, feed_url: U) -> Subscription {
+        Subscription {
+            title: title.into(),
+            feed_url: feed_url.into(),
+            ..Subscription::default()
+        }
+    }
+
+    /// Builds a subscription to a feed, which must have a `feed_url`
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::Feed;
+    /// # use jsonfeed::opml::Subscription;
+    /// # fn main() {
+    /// let feed = Feed::builder()
+    ///                 .title("my feed")
+    ///                 .feed_url("https://example.com/feed.json")
+    ///                 .build()
+    ///                 .unwrap();
+    /// let subscription = Subscription::from_feed(&feed).unwrap();
+    /// assert_eq!(subscription.feed_url, "https://example.com/feed.json");
+    /// # }
+    /// ```
+    pub fn from_feed(feed: &Feed) -> Result<Subscription> {
+        match feed.feed_url {
+            Some(ref feed_url) => Ok(Subscription {
+                title: feed.title.clone(),
+                feed_url: feed_url.clone(),
+                home_page_url: feed.home_page_url.clone(),
+                category: vec![],
+            }),
+            None => Err(Error::missing_field("feed_url")),
+        }
+    }
+
+    /// Files the subscription under a folder, inside any it is already in
+    pub fn category<S: Into<String>>(mut self, folder: S) -> Subscription {
+        self.category.push(folder.into());
+        self
+    }
+}
+
+/// Reads the subscriptions in an OPML document
+pub fn from_reader<R: Read>(reader: R) -> Result<Vec<Subscription>> {
+    let root = dom::parse(reader)?;
+    if !root.is(None, "opml") {
+        return Err(Error::invalid_value(format!("expected an OPML document, found <{}>", root.qualified), ""));
+    }
+    let body = match root.child(None, "body") {
+        Some(body) => body,
+        None => return Err(Error::missing_field("body")),
+    };
+    let mut subscriptions = vec![];
+    read_outlines(body, &mut vec![], &mut subscriptions);
+    Ok(subscriptions)
+}
+
+fn read_outlines(parent: &Element, path: &mut Vec<String>, subscriptions: &mut Vec<Subscription>) {
+    for outline in parent.children.iter().filter(|e| e.is(None, "outline")) {
+        let title = outline.attr("title").or_else(|| outline.attr("text")).unwrap_or("");
+        match outline.attr("xmlUrl") {
+            Some(feed_url) => {
+                let mut category = path.clone();
+                // OPML 2.0 also allows slash-delimited categories, of
+                // which we only keep the first
+                if category.is_empty() {
+                    if let Some(categories) = outline.attr("category") {
+                        let first = categories.split(',').next().unwrap_or("");
+                        category = first.split('/')
+                            .filter(|s| !s.trim().is_empty())
+                            .map(|s| s.trim().to_string())
+                            .collect();
+                    }
+                }
+                subscriptions.push(Subscription {
+                    title: title.to_string(),
+                    feed_url: feed_url.to_string(),
+                    home_page_url: outline.attr("htmlUrl").map(|s| s.to_string()),
+                    category,
+                });
+            },
+            None => {
+                path.push(title.to_string());
+                read_outlines(outline, path, subscriptions);
+                path.pop();
+            },
+        }
+    }
+}
+
+/// Writes subscriptions as an OPML 2.0 document with the given title
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::opml::{self, Subscription};
+/// # fn main() {
+/// let subscriptions = vec![
+///     Subscription::new("my feed", "https://example.com/feed.json").category("Tech"),
+/// ];
+/// let xml = opml::to_string("my subscriptions", &subscriptions).unwrap();
+/// assert_eq!(opml::from_reader(xml.as_bytes()).unwrap(), subscriptions);
+/// # }
+/// ```
+pub fn to_string(title: &str, subscriptions: &[Subscription]) -> Result<String> {
+    let mut w = EmitterConfig::new().perform_indent(true).create_writer(Vec::new());
+    w.write(XmlEvent::start_element("opml").attr("version", "2.0"))?;
+    w.write(XmlEvent::start_element("head"))?;
+    dom::write_text(&mut w, "title", title)?;
+    w.write(XmlEvent::end_element())?;
+    w.write(XmlEvent::start_element("body"))?;
+    let subscriptions: Vec<&Subscription> = subscriptions.iter().collect();
+    write_outlines(&mut w, &subscriptions, 0)?;
+    w.write(XmlEvent::end_element())?;
+    w.write(XmlEvent::end_element())?;
+    Ok(String::from_utf8(w.into_inner()).expect("xml-rs writes UTF-8"))
+}
+
+/// Writes the subscriptions filed `depth` folders deep, followed by a
+/// folder for each of the deeper folders, in the order they first appear
+fn write_outlines(w: &mut EventWriter<Vec<u8>>, subscriptions: &[&Subscription], depth: usize) -> Result<()> {
+    let mut folders: Vec<&str> = vec![];
+    for subscription in subscriptions {
+        if subscription.category.len() == depth {
+            let mut outline = XmlEvent::start_element("outline")
+                .attr("type", "rss")
+                .attr("text", &subscription.title)
+                .attr("title", &subscription.title)
+                .attr("xmlUrl", &subscription.feed_url);
+            if let Some(ref home_page_url) = subscription.home_page_url {
+                outline = outline.attr("htmlUrl", home_page_url);
+            }
+            w.write(outline)?;
+            w.write(XmlEvent::end_element())?;
+        } else if !folders.contains(&&subscription.category[depth][..]) {
+            folders.push(&subscription.category[depth]);
+        }
+    }
+    for folder in folders {
+        w.write(XmlEvent::start_element("outline").attr("text", folder))?;
+        let inside: Vec<&Subscription> = subscriptions.iter()
+            .filter(|s| s.category.len() > depth && s.category[depth] == folder)
+            .cloned()
+            .collect();
+        write_outlines(w, &inside, depth + 1)?;
+        w.write(XmlEvent::end_element())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_opml() {
+        let xml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>subscriptions</title></head>
+  <body>
+    <outline text="loose" type="rss" xmlUrl="https://example.com/loose.json"/>
+    <outline text="Tech">
+      <outline text="Rust">
+        <outline text="rust blog" title="The Rust Blog" type="rss" xmlUrl="https://example.com/rust.xml" htmlUrl="https://example.com/rust"/>
+      </outline>
+    </outline>
+    <outline text="tagged" type="rss" xmlUrl="https://example.com/tagged.json" category="/News/World,/Other"/>
+  </body>
+</opml>"#;
+        let subscriptions = from_reader(xml.as_bytes()).unwrap();
+        assert_eq!(subscriptions, vec![
+            Subscription::new("loose", "https://example.com/loose.json"),
+            Subscription {
+                home_page_url: Some("https://example.com/rust".to_string()),
+                ..Subscription::new("The Rust Blog", "https://example.com/rust.xml").category("Tech").category("Rust")
+            },
+            Subscription::new("tagged", "https://example.com/tagged.json").category("News").category("World"),
+        ]);
+    }
+
+    #[test]
+    fn write_opml() {
+        let subscriptions = vec![
+            Subscription::new("a", "https://example.com/a.json").category("Tech").category("Rust"),
+            Subscription::new("b", "https://example.com/b.json"),
+            Subscription::new("c", "https://example.com/c.json").category("News"),
+            Subscription::new("d", "https://example.com/d.json").category("Tech"),
+        ];
+        let xml = to_string("subscriptions", &subscriptions).unwrap();
+        assert!(xml.contains("<title>subscriptions</title>"), "{}", xml);
+        let mut read = from_reader(xml.as_bytes()).unwrap();
+        read.sort_by(|a, b| a.title.cmp(&b.title));
+        assert_eq!(read, subscriptions);
+    }
+
+    #[test]
+    fn subscription_from_feed() {
+        let mut feed = Feed {
+            title: "my feed".to_string(),
+            home_page_url: Some("https://example.com/".to_string()),
+            ..Feed::default()
+        };
+        assert!(Subscription::from_feed(&feed).is_err());
+        feed.feed_url = Some("https://example.com/feed.json".to_string());
+        let subscription = Subscription::from_feed(&feed).unwrap();
+        assert_eq!(subscription.title, "my feed");
+        assert_eq!(subscription.home_page_url, Some("https://example.com/".to_string()));
+    }
+}
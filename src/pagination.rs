@@ -0,0 +1,335 @@
+//! Following `next_url` through a paginated feed
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::vec;
+
+use date;
+use errors::*;
+use feed::Feed;
+use item::Item;
+
+/// Pages are followed no further than this, unless `Paginator::max_pages`
+/// says otherwise
+const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Fetches the document at a URL
+///
+/// This crate doesn't make HTTP requests itself; implement this with the
+/// client of your choice. Closures taking a URL and returning the bytes
+/// are fetchers too.
+pub trait Fetcher {
+    fn fetch(&mut self, url: &str) -> Result<Vec<u8>>;
+}
+
+impl<F> Fetcher for F
+    where F: FnMut(&str) -> Result<Vec<u8>>
+{
+    fn fetch(&mut self, url: &str) -> Result<Vec<u8>> {
+        self(url)
+    }
+}
+
+/// An iterator over the pages of a feed, following each page's `next_url`
+///
+/// Items already seen on an earlier page, by id, are removed from later
+/// pages. Iteration stops after the last page, after `max_pages` pages,
+/// or after the first error. A `next_url` pointing back at a page already
+/// fetched is an error.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::Paginator;
+/// # fn main() {
+/// let fetcher = |url: &str| -> jsonfeed::Result<Vec<u8>> {
+///     let json = match url {
+///         "https://example.com/1.json" => r#"{"version": "https://jsonfeed.org/version/1", "title": "", "items": [],
+///                                            "next_url": "https://example.com/2.json"}"#,
+///         _ => r#"{"version": "https://jsonfeed.org/version/1", "title": "", "items": []}"#,
+///     };
+///     Ok(json.as_bytes().to_vec())
+/// };
+/// let pages = Paginator::new(fetcher, "https://example.com/1.json");
+/// assert_eq!(pages.count(), 2);
+/// # }
+/// ```
+pub struct Paginator<F> {
+    fetcher: F,
+    next_url: Option<String>,
+    visited: HashSet<String>,
+    ids: HashSet<String>,
+    max_pages: usize,
+}
+
+impl<F: Fetcher> Paginator<F> {
+    /// Starts from the page at `url`
+    pub fn new<S: Into<String>>(fetcher: F, url: S) -> Paginator<F> {
+        Paginator {
+            fetcher,
+            next_url: Some(url.into()),
+            visited: HashSet::new(),
+            ids: HashSet::new(),
+            max_pages: DEFAULT_MAX_PAGES,
+        }
+    }
+
+    /// Stops after `max_pages` pages. The default is 100.
+    pub fn max_pages(mut self, max_pages: usize) -> Paginator<F> {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// Flattens the pages into their items
+    pub fn items(self) -> PaginatedItems<F> {
+        PaginatedItems {
+            pages: self,
+            items: Vec::new().into_iter(),
+        }
+    }
+
+    fn fetch(&mut self, url: String) -> Result<Feed> {
+        if !self.visited.insert(url.clone()) {
+            return Err(Error::invalid_value(format!("next_url loops back to '{}'", url), "next_url"));
+        }
+        let bytes = self.fetcher.fetch(&url)?;
+        let mut feed = ::from_slice(&bytes)?;
+        let ids = &mut self.ids;
+        feed.items.retain(|item| ids.insert(item.id.clone()));
+        self.next_url = feed.next_url.clone();
+        Ok(feed)
+    }
+}
+
+impl<F: Fetcher> Iterator for Paginator<F> {
+    type Item = Result<Feed>;
+
+    fn next(&mut self) -> Option<Result<Feed>> {
+        if self.visited.len() >= self.max_pages {
+            return None;
+        }
+        let url = self.next_url.take()?;
+        Some(self.fetch(url))
+    }
+}
+
+/// An iterator over the items of every page of a feed, returned by
+/// `Paginator::items`
+pub struct PaginatedItems<F> {
+    pages: Paginator<F>,
+    items: vec::IntoIter<Item>,
+}
+
+impl<F: Fetcher> Iterator for PaginatedItems<F> {
+    type Item = Result<Item>;
+
+    fn next(&mut self) -> Option<Result<Item>> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+            match self.pages.next()? {
+                Ok(feed) => self.items = feed.items.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl Feed {
+    /// Splits the feed into pages of at most `page_size` items, newest
+    /// first
+    ///
+    /// Every page has the feed's metadata, with `feed_url` set to
+    /// `url_template` with `{page}` replaced by the page number, starting
+    /// at 1, and `next_url` pointing at the following page. Items without
+    /// a `date_published` come after the dated ones, in their original
+    /// order. A feed without items gives a single, empty page.
+    ///
+    /// Fails with `Error::InvalidArgument` if `page_size` is 0, or if
+    /// `url_template` doesn't contain `{page}`.
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::{Feed, Item};
+    /// # fn main() {
+    /// let mut feed = Feed::builder().title("archive").build().unwrap();
+    /// for i in 0..5 {
+    ///     feed.items.push(Item::builder().id(i.to_string()).content_text("").build().unwrap());
+    /// }
+    /// let pages = feed.paginate(2, "https://example.com/archive/{page}.json").unwrap();
+    /// assert_eq!(pages.len(), 3);
+    /// assert_eq!(pages[0].next_url, Some("https://example.com/archive/2.json".to_string()));
+    /// assert_eq!(pages[2].next_url, None);
+    /// # }
+    /// ```
+    pub fn paginate(&self, page_size: usize, url_template: &str) -> Result<Vec<Feed>> {
+        if page_size == 0 {
+            return Err(Error::invalid_argument("page_size", "page size must be non-zero"));
+        }
+        if !url_template.contains("{page}") {
+            return Err(Error::invalid_argument("url_template",
+                                               format!("url template '{}' doesn't contain {{page}}", url_template)));
+        }
+
+        let mut items: Vec<&Item> = self.items.iter().collect();
+        items.sort_by_key(|item| Reverse(item.date_published.as_deref().and_then(date::timestamp)));
+
+        let url = |page: usize| url_template.replace("{page}", &page.to_string());
+        let chunks: Vec<&[&Item]> = if items.is_empty() {
+            vec![&[]]
+        } else {
+            items.chunks(page_size).collect()
+        };
+        let pages = chunks.len();
+        Ok(chunks.into_iter().enumerate().map(|(i, chunk)| {
+            let mut page = self.metadata();
+            page.items = chunk.iter().map(|&item| item.clone()).collect();
+            page.feed_url = Some(url(i + 1));
+            page.next_url = if i + 1 < pages { Some(url(i + 2)) } else { None };
+            page
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::collections::HashMap;
+
+    fn page(ids: &[&str], next_url: Option<&str>) -> Vec<u8> {
+        let items: Vec<String> = ids.iter()
+            .map(|id| format!(r#"{{"id":"{}","content_text":""}}"#, id))
+            .collect();
+        let next_url = next_url.map_or(String::new(), |url| format!(r#","next_url":"{}""#, url));
+        format!(r#"{{"version":"https://jsonfeed.org/version/1","title":"","items":[{}]{}}}"#,
+                items.join(","), next_url).into_bytes()
+    }
+
+    fn fetcher(pages: Vec<(&'static str, Vec<u8>)>) -> impl FnMut(&str) -> Result<Vec<u8>> {
+        let pages: HashMap<&str, Vec<u8>> = pages.into_iter().collect();
+        move |url: &str| match pages.get(url) {
+            Some(bytes) => Ok(bytes.clone()),
+            None => Err(Error::Io(io::Error::new(io::ErrorKind::NotFound, url.to_string()))),
+        }
+    }
+
+    fn ids(items: Vec<Result<Item>>) -> Vec<String> {
+        items.into_iter().map(|item| item.unwrap().id).collect()
+    }
+
+    #[test]
+    fn follows_next_url() {
+        let fetcher = fetcher(vec![
+            ("https://example.com/1", page(&["a", "b"], Some("https://example.com/2"))),
+            ("https://example.com/2", page(&["b", "c"], Some("https://example.com/3"))),
+            ("https://example.com/3", page(&["d"], None)),
+        ]);
+        let pages: Vec<Feed> = Paginator::new(fetcher, "https://example.com/1").map(|page| page.unwrap()).collect();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[1].items.len(), 1);
+        assert_eq!(pages[1].items[0].id, "c");
+    }
+
+    #[test]
+    fn flattens_items() {
+        let fetcher = fetcher(vec![
+            ("https://example.com/1", page(&["a", "b"], Some("https://example.com/2"))),
+            ("https://example.com/2", page(&["b", "c"], None)),
+        ]);
+        assert_eq!(ids(Paginator::new(fetcher, "https://example.com/1").items().collect()), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn stops_at_max_pages() {
+        let fetcher = fetcher(vec![
+            ("https://example.com/1", page(&["a"], Some("https://example.com/2"))),
+            ("https://example.com/2", page(&["b"], Some("https://example.com/3"))),
+            ("https://example.com/3", page(&["c"], None)),
+        ]);
+        assert_eq!(ids(Paginator::new(fetcher, "https://example.com/1").max_pages(2).items().collect()), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn detects_loops() {
+        let fetcher = fetcher(vec![
+            ("https://example.com/1", page(&["a"], Some("https://example.com/2"))),
+            ("https://example.com/2", page(&["b"], Some("https://example.com/1"))),
+        ]);
+        let items: Vec<Result<Item>> = Paginator::new(fetcher, "https://example.com/1").items().collect();
+        assert_eq!(items.len(), 3);
+        match items[2] {
+            Err(Error::InvalidValue { ref location, .. }) => assert_eq!(location.path, "next_url"),
+            ref other => panic!("expected an invalid value error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stops_after_errors() {
+        let fetcher = fetcher(vec![
+            ("https://example.com/1", page(&["a"], Some("https://example.com/2"))),
+        ]);
+        let mut pages = Paginator::new(fetcher, "https://example.com/1");
+        assert!(pages.next().unwrap().is_ok());
+        match pages.next() {
+            Some(Err(Error::Io(_))) => {},
+            other => panic!("expected an io error, got {:?}", other),
+        }
+        assert!(pages.next().is_none());
+    }
+
+    fn archive() -> Feed {
+        let mut feed = Feed::builder()
+            .title("archive")
+            .home_page_url("https://example.com/")
+            .build()
+            .unwrap();
+        for (id, day) in &[("a", Some(3)), ("b", None), ("c", Some(5)), ("d", Some(1)), ("e", Some(4))] {
+            let mut item = Item::builder().id(*id).content_text(*id).build().unwrap();
+            item.date_published = day.map(|day| format!("2017-01-0{}T00:00:00Z", day));
+            feed.items.push(item);
+        }
+        feed
+    }
+
+    #[test]
+    fn paginate() {
+        let pages = archive().paginate(2, "https://example.com/{page}.json").unwrap();
+        assert_eq!(pages.len(), 3);
+        let ids: Vec<Vec<&str>> = pages.iter()
+            .map(|page| page.items.iter().map(|item| &item.id[..]).collect())
+            .collect();
+        assert_eq!(ids, vec![vec!["c", "e"], vec!["a", "d"], vec!["b"]]);
+        assert_eq!(pages[1].feed_url, Some("https://example.com/2.json".to_string()));
+        assert_eq!(pages[1].next_url, Some("https://example.com/3.json".to_string()));
+        assert_eq!(pages[2].next_url, None);
+        for page in &pages {
+            assert_eq!(page.title, "archive");
+            assert_eq!(page.validate(), vec![]);
+        }
+    }
+
+    #[test]
+    fn paginate_empty() {
+        let feed = Feed { items: vec![], ..archive() };
+        let pages = feed.paginate(10, "https://example.com/{page}.json").unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].next_url, None);
+    }
+
+    #[test]
+    fn paginate_invalid() {
+        match archive().paginate(0, "https://example.com/{page}.json") {
+            Err(Error::InvalidArgument { name, .. }) => assert_eq!(name, "page_size"),
+            other => panic!("expected an invalid argument error, got {:?}", other),
+        }
+        match archive().paginate(2, "https://example.com/archive.json") {
+            Err(e @ Error::InvalidArgument { .. }) => {
+                assert_eq!(e.path(), None);
+                assert_eq!(e.to_string(),
+                           "invalid argument `url_template`: url template 'https://example.com/archive.json' doesn't contain {page}");
+            },
+            other => panic!("expected an invalid argument error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,412 @@
+//! Strict and lenient parsing
+//!
+//! Real-world feeds are not always valid. In lenient mode the parser
+//! drops whatever it can't make sense of, rather than failing the
+//! whole document, and records what it dropped in a `ParseReport`.
+
+use std::fmt;
+use std::default::Default;
+use std::io::Read;
+use std::mem;
+
+use serde_json::{self, Map, Value};
+use serde_path_to_error::{self, Segment};
+
+use errors::*;
+use date;
+use extensions;
+use feed::{self, Feed};
+use item::{self, Item};
+
+/// Options for `from_str_with` and `from_reader_with`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Fail on anything that doesn't match the spec. When false, unknown
+    /// keys are ignored, wrong-typed optional fields are dropped and
+    /// invalid items are skipped.
+    pub strict: bool,
+    /// Check that `date_published` and `date_modified` are RFC 3339
+    /// timestamps, as the spec requires. An invalid date fails a strict
+    /// parse, naming the item and field, and is dropped otherwise.
+    /// `from_str` and friends never check dates.
+    pub check_dates: bool,
+}
+
+impl ParseOptions {
+    /// Options that reject invalid feeds, like `from_str` does
+    pub fn strict() -> ParseOptions {
+        ParseOptions { strict: true, check_dates: false }
+    }
+
+    /// Options that recover from as much as possible
+    pub fn lenient() -> ParseOptions {
+        ParseOptions { strict: false, check_dates: false }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions::strict()
+    }
+}
+
+/// Something the parser recovered from in lenient mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseWarning {
+    /// A key that isn't part of the spec, and isn't an extension, was ignored
+    UnknownField { path: String },
+    /// A field that couldn't be parsed was dropped
+    InvalidField { path: String, message: String },
+    /// An item that couldn't be parsed was skipped
+    SkippedItem { path: String, message: String },
+    /// The feed declares a version this crate doesn't know
+    UnsupportedVersion { version: String },
+}
+
+impl ParseWarning {
+    /// The path of the offending value, e.g. `items[3].url`
+    pub fn path(&self) -> &str {
+        match *self {
+            ParseWarning::UnknownField { ref path } |
+            ParseWarning::InvalidField { ref path, .. } |
+            ParseWarning::SkippedItem { ref path, .. } => path,
+            ParseWarning::UnsupportedVersion { .. } => "version",
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseWarning::UnknownField { ref path } => {
+                write!(f, "{}: unknown field ignored", path)
+            },
+            ParseWarning::InvalidField { ref path, ref message } => {
+                write!(f, "{}: invalid field dropped: {}", path, message)
+            },
+            ParseWarning::SkippedItem { ref path, ref message } => {
+                write!(f, "{}: item skipped: {}", path, message)
+            },
+            ParseWarning::UnsupportedVersion { ref version } => {
+                write!(f, "version: unsupported JSON Feed version '{}'", version)
+            },
+        }
+    }
+}
+
+/// What the parser recovered from while parsing a feed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParseReport {
+    pub warnings: Vec<ParseWarning>,
+}
+
+impl ParseReport {
+    /// Returns true if the feed was parsed without any problems
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    fn warn(&mut self, warning: ParseWarning) {
+        self.warnings.push(warning);
+    }
+}
+
+/// Parses a feed from a string slice, using the given options
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::ParseOptions;
+/// # fn main() {
+/// let json = r#"{
+///     "version": "https://jsonfeed.org/version/1",
+///     "title": "my feed",
+///     "items": [
+///         {"id": "1", "content_text": "hello", "rating": 5},
+///         {"content_text": "no id"}
+///     ]
+/// }"#;
+/// assert!(jsonfeed::from_str(json).is_err());
+///
+/// let (feed, report) = jsonfeed::from_str_with(json, &ParseOptions::lenient()).unwrap();
+/// assert_eq!(feed.items.len(), 1);
+/// assert_eq!(report.warnings.len(), 2);
+/// # }
+/// ```
+pub fn from_str_with(s: &str, options: &ParseOptions) -> Result<(Feed, ParseReport)> {
+    let parsed = if options.strict {
+        (::from_str(s)?, ParseReport::default())
+    } else {
+        lenient(serde_json::from_str(s)?)?
+    };
+    check_dates(parsed, options)
+}
+
+/// Parses a feed from an IO stream, using the given options
+pub fn from_reader_with<R: Read>(r: R, options: &ParseOptions) -> Result<(Feed, ParseReport)> {
+    let parsed = if options.strict {
+        (::from_reader(r)?, ParseReport::default())
+    } else {
+        lenient(serde_json::from_reader(r)?)?
+    };
+    check_dates(parsed, options)
+}
+
+fn check_dates((mut feed, mut report): (Feed, ParseReport), options: &ParseOptions)
+        -> Result<(Feed, ParseReport)>
+{
+    if !options.check_dates {
+        return Ok((feed, report));
+    }
+    for (i, item) in feed.items.iter_mut().enumerate() {
+        let id = &item.id;
+        for (field, value) in [("date_published", &mut item.date_published),
+                               ("date_modified", &mut item.date_modified)] {
+            let message = match value.as_ref().map(|d| date::check(d)) {
+                Some(Err(message)) => message,
+                _ => continue,
+            };
+            let path = format!("items[{}].{}", i, field);
+            if options.strict {
+                let message = format!("invalid date in field `{}` of item '{}': {}", field, id, message);
+                return Err(Error::invalid_value(message, path));
+            }
+            *value = None;
+            report.warn(ParseWarning::InvalidField { path, message });
+        }
+    }
+    Ok((feed, report))
+}
+
+fn lenient(value: Value) -> Result<(Feed, ParseReport)> {
+    let mut report = ParseReport::default();
+    let mut obj = match value {
+        Value::Object(obj) => obj,
+        other => return Ok((serde_json::from_value(other)?, report)),
+    };
+
+    let items = obj.remove("items");
+    obj.insert("items".to_string(), Value::Array(vec![]));
+    remove_unknown_fields(&mut obj, feed::FIELDS, "", &mut report);
+
+    let mut probe = Map::new();
+    for key in &["version", "title", "items"] {
+        if let Some(v) = obj.get(*key) {
+            probe.insert(key.to_string(), v.clone());
+        }
+    }
+    serde_json::from_value::<Feed>(Value::Object(probe))?;
+    remove_invalid_fields::<Feed>(&mut obj, "", &mut report);
+
+    let mut feed: Feed = serde_json::from_value(Value::Object(obj))?;
+    if !feed.version.is_known() {
+        report.warn(ParseWarning::UnsupportedVersion { version: feed.version.to_string() });
+    }
+
+    match items {
+        Some(Value::Array(items)) => {
+            for (i, item) in items.into_iter().enumerate() {
+                if let Some(item) = lenient_item(item, &format!("items[{}]", i), &mut report) {
+                    feed.items.push(item);
+                }
+            }
+        },
+        Some(other) => {
+            report.warn(ParseWarning::InvalidField {
+                path: "items".to_string(),
+                message: format!("expected an array, found {}", other),
+            });
+        },
+        None => {
+            report.warn(ParseWarning::InvalidField {
+                path: "items".to_string(),
+                message: "missing field `items`".to_string(),
+            });
+        },
+    }
+
+    Ok((feed, report))
+}
+
+fn lenient_item(value: Value, path: &str, report: &mut ParseReport) -> Option<Item> {
+    let mut obj = match value {
+        Value::Object(obj) => obj,
+        other => {
+            report.warn(ParseWarning::SkippedItem {
+                path: path.to_string(),
+                message: format!("expected an object, found {}", other),
+            });
+            return None;
+        },
+    };
+
+    // The spec asks readers to coerce numeric ids to strings
+    let numeric_id = match obj.get("id") {
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
+    if let Some(id) = numeric_id {
+        obj.insert("id".to_string(), Value::String(id));
+    }
+    remove_unknown_fields(&mut obj, item::FIELDS, path, report);
+
+    let mut probe = Map::new();
+    probe.insert("id".to_string(), obj.get("id").cloned().unwrap_or(Value::Null));
+    probe.insert("content_text".to_string(), Value::String("".to_string()));
+    if let Err(e) = serde_json::from_value::<Item>(Value::Object(probe)) {
+        report.warn(ParseWarning::SkippedItem { path: path.to_string(), message: json_message(&e) });
+        return None;
+    }
+    remove_invalid_fields::<Item>(&mut obj, path, report);
+
+    match serde_json::from_value(Value::Object(obj)) {
+        Ok(item) => Some(item),
+        Err(e) => {
+            report.warn(ParseWarning::SkippedItem { path: path.to_string(), message: json_message(&e) });
+            None
+        },
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn remove_unknown_fields(obj: &mut Map<String, Value>,
+                         known: &[&str],
+                         path: &str,
+                         report: &mut ParseReport)
+{
+    let unknown: Vec<String> = obj.keys()
+        .filter(|k| !known.contains(&&k[..]) && !extensions::is_extension_key(k))
+        .cloned()
+        .collect();
+    for key in unknown {
+        obj.remove(&key);
+        report.warn(ParseWarning::UnknownField { path: join(path, &key) });
+    }
+}
+
+/// Drops the fields of `obj` that keep it from deserializing as `T`, one
+/// at a time, going by the path of each error. Stops at the first error
+/// that isn't in a field, e.g. a missing one.
+fn remove_invalid_fields<T>(obj: &mut Map<String, Value>, path: &str, report: &mut ParseReport)
+    where T: ::serde::de::DeserializeOwned
+{
+    loop {
+        let value = Value::Object(mem::take(obj));
+        let result = serde_path_to_error::deserialize::<_, T>(&value);
+        if let Value::Object(value) = value {
+            *obj = value;
+        }
+        let e = match result {
+            Ok(_) => return,
+            Err(e) => e,
+        };
+        let key = match e.path().iter().next() {
+            Some(Segment::Map { key }) if obj.contains_key(key) => key.clone(),
+            _ => return,
+        };
+        obj.remove(&key);
+        report.warn(ParseWarning::InvalidField { path: join(path, &key), message: json_message(e.inner()) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use item::Content;
+
+    #[test]
+    fn strict_is_default() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"id":"1","content_text":"","bogus":1}]}"#;
+        assert!(from_str_with(json, &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn lenient_unknown_fields() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","bogus":true,"items":[{"id":"1","content_text":"","bogus":1,"_ext":1}]}"#;
+        let (feed, report) = from_str_with(json, &ParseOptions::lenient()).unwrap();
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].extensions.len(), 1);
+        assert_eq!(
+            report.warnings,
+            vec![
+                ParseWarning::UnknownField { path: "bogus".to_string() },
+                ParseWarning::UnknownField { path: "items[0].bogus".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_invalid_fields() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","expired":"no","items":[{"id":7,"content_html":5,"content_text":"text","tags":"a,b","url":"http://example.com"}]}"#;
+        let (feed, report) = from_str_with(json, &ParseOptions::lenient()).unwrap();
+        assert_eq!(feed.expired, None);
+        assert_eq!(feed.items[0].id, "7");
+        assert_eq!(feed.items[0].content, Content::Text("text".to_string()));
+        assert_eq!(feed.items[0].tags, None);
+        assert_eq!(feed.items[0].url, Some("http://example.com".to_string()));
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec!["expired", "items[0].content_html", "items[0].tags"]);
+    }
+
+    #[test]
+    fn lenient_skipped_items() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"content_text":""},{"id":"2"},3,{"id":"4","content_text":""}]}"#;
+        let (feed, report) = from_reader_with(json.as_bytes(), &ParseOptions::lenient()).unwrap();
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].id, "4");
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec!["items[0]", "items[1]", "items[2]"]);
+    }
+
+    #[test]
+    fn lenient_still_requires_title() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","items":[]}"#;
+        assert!(from_str_with(json, &ParseOptions::lenient()).is_err());
+    }
+
+    #[test]
+    fn lenient_unknown_version() {
+        let json = r#"{"version":"https://jsonfeed.org/version/2","title":"","items":[]}"#;
+        let (_, report) = from_str_with(json, &ParseOptions::lenient()).unwrap();
+        assert_eq!(
+            report.warnings,
+            vec![ParseWarning::UnsupportedVersion { version: "https://jsonfeed.org/version/2".to_string() }]
+        );
+    }
+
+    #[test]
+    fn invalid_date() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","items":[{"id":"1","content_text":"","date_published":"last week"}]}"#;
+        let (feed, report) = from_str_with(json, &ParseOptions::strict()).unwrap();
+        assert_eq!(feed.items[0].date_published, Some("last week".to_string()));
+        assert!(report.is_empty());
+
+        let options = ParseOptions { check_dates: true, ..ParseOptions::strict() };
+        match from_str_with(json, &options) {
+            Err(Error::InvalidValue { message, location }) => {
+                assert_eq!(location.path, "items[0].date_published");
+                assert!(message.contains("item '1'"), "{}", message);
+            },
+            other => panic!("expected an invalid value error, got {:?}", other),
+        }
+
+        let options = ParseOptions { check_dates: true, ..ParseOptions::lenient() };
+        let (feed, report) = from_str_with(json, &options).unwrap();
+        assert_eq!(feed.items[0].date_published, None);
+        assert_eq!(report.warnings[0].path(), "items[0].date_published");
+    }
+
+    #[test]
+    fn urls_are_not_checked() {
+        let json = r#"{"version":"https://jsonfeed.org/version/1","title":"","home_page_url":"not a url","items":[]}"#;
+        let (feed, report) = from_str_with(json, &ParseOptions::strict()).unwrap();
+        assert_eq!(feed.home_page_url, Some("not a url".to_string()));
+        assert!(report.warnings.is_empty());
+    }
+}
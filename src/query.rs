@@ -0,0 +1,231 @@
+//! Selecting the items of a feed
+
+use date;
+use errors::*;
+use feed::{Author, Feed};
+use item::Item;
+
+/// A filter over the items of a feed, returned by `Feed::query`
+///
+/// Every condition must hold for an item to be selected. Items are
+/// selected in the order they appear in the feed.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::{Attachment, Feed, Item};
+/// # fn main() {
+/// let feed = Feed::builder()
+///     .title("podcast")
+///     .item(Item::builder()
+///             .id("1")
+///             .content_text("episode 1")
+///             .tag("rust")
+///             .attachment(Attachment::builder("https://example.com/1.mp3", "audio/mpeg").build())
+///             .build().unwrap())
+///     .item(Item::builder().id("2").content_text("show notes").tag("rust").build().unwrap())
+///     .build().unwrap();
+///
+/// let episodes = feed.query().tag("rust").has_attachment_mime("audio/*").items();
+/// assert_eq!(episodes.len(), 1);
+/// assert_eq!(episodes[0].id, "1");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query<'a> {
+    feed: &'a Feed,
+    tags: Vec<String>,
+    since: Option<i64>,
+    author_name: Option<String>,
+    attachment_mime: Option<String>,
+    limit: Option<usize>,
+}
+
+impl<'a> Query<'a> {
+    /// Selects items with this tag. Can be given several times, in which
+    /// case items must have every tag.
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Query<'a> {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Selects items published at or after `date`, an RFC 3339 timestamp.
+    /// Items without a `date_published` are left out.
+    ///
+    /// Fails with `Error::InvalidArgument` if `date` isn't an RFC 3339
+    /// timestamp.
+    pub fn since(mut self, date: &str) -> Result<Query<'a>> {
+        if let Err(message) = date::check(date) {
+            return Err(Error::invalid_argument("since", message));
+        }
+        self.since = date::timestamp(date);
+        Ok(self)
+    }
+
+    /// Selects items with an author of this name. Items without authors
+    /// are attributed to the authors of the feed.
+    pub fn author_name<S: Into<String>>(mut self, name: S) -> Query<'a> {
+        self.author_name = Some(name.into());
+        self
+    }
+
+    /// Selects items with an attachment of this MIME type, ignoring case.
+    /// The subtype, or both type and subtype, may be `*`, e.g. `audio/*`.
+    pub fn has_attachment_mime<S: Into<String>>(mut self, mime_type: S) -> Query<'a> {
+        self.attachment_mime = Some(mime_type.into());
+        self
+    }
+
+    /// Selects no more than `limit` items
+    pub fn limit(mut self, limit: usize) -> Query<'a> {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The selected items
+    pub fn items(&self) -> Vec<&'a Item> {
+        self.feed.items.iter()
+            .filter(|item| self.matches(item))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// A copy of the feed with only the selected items
+    pub fn to_feed(&self) -> Feed {
+        let mut feed = self.feed.metadata();
+        feed.items = self.items().into_iter().cloned().collect();
+        feed
+    }
+
+    fn matches(&self, item: &Item) -> bool {
+        if !self.tags.iter().all(|tag| item.tags.iter().flatten().any(|t| t == tag)) {
+            return false;
+        }
+        if let Some(since) = self.since {
+            match item.date_published.as_deref().and_then(date::timestamp) {
+                Some(published) if published >= since => {},
+                _ => return false,
+            }
+        }
+        if let Some(ref name) = self.author_name {
+            let (author, authors) = if item.author.is_some() || item.authors.is_some() {
+                (&item.author, &item.authors)
+            } else {
+                (&self.feed.author, &self.feed.authors)
+            };
+            let has_name = |author: &Author| author.name.as_ref() == Some(name);
+            if !author.iter().chain(authors.iter().flatten()).any(has_name) {
+                return false;
+            }
+        }
+        if let Some(ref pattern) = self.attachment_mime {
+            if !item.attachments.iter().flatten().any(|a| mime_matches(pattern, &a.mime_type)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Matches a MIME type against a pattern like `audio/*`, ignoring case
+/// and any parameters
+fn mime_matches(pattern: &str, mime_type: &str) -> bool {
+    fn split(s: &str) -> (String, String) {
+        let essence = s.split(';').next().unwrap_or("").trim().to_lowercase();
+        let mut parts = essence.splitn(2, '/');
+        let type_ = parts.next().unwrap_or("").to_string();
+        let subtype = parts.next().unwrap_or("").to_string();
+        (type_, subtype)
+    }
+
+    let (type_pattern, subtype_pattern) = split(pattern);
+    let (type_, subtype) = split(mime_type);
+    (type_pattern == "*" || type_pattern == type_) &&
+        (subtype_pattern == "*" || subtype_pattern == subtype)
+}
+
+impl Feed {
+    /// Starts a query selecting all the items of the feed
+    pub fn query(&self) -> Query<'_> {
+        Query {
+            feed: self,
+            tags: vec![],
+            since: None,
+            author_name: None,
+            attachment_mime: None,
+            limit: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feed::Attachment;
+
+    fn feed() -> Feed {
+        let items = vec![
+            Item::builder().id("1").content_text("").tag("rust").tag("audio")
+                .date_published("2017-01-01T00:00:00Z")
+                .attachment(Attachment::builder("https://example.com/1.mp3", "Audio/MPEG").build())
+                .build().unwrap(),
+            Item::builder().id("2").content_text("").tag("rust")
+                .date_published("2017-01-03T00:00:00+01:00")
+                .author(Author::builder().name("bob").build())
+                .attachment(Attachment::builder("https://example.com/2.png", "image/png").build())
+                .build().unwrap(),
+            Item::builder().id("3").content_text("").tag("go")
+                .date_published("2017-01-05T00:00:00Z")
+                .build().unwrap(),
+            Item::builder().id("4").content_text("").tag("rust").build().unwrap(),
+        ];
+        Feed {
+            title: "feed".to_string(),
+            author: Some(Author::builder().name("alice").build()),
+            items,
+            ..Feed::default()
+        }
+    }
+
+    fn ids(items: Vec<&Item>) -> Vec<&str> {
+        items.into_iter().map(|item| &item.id[..]).collect()
+    }
+
+    #[test]
+    fn query() {
+        let feed = feed();
+        assert_eq!(ids(feed.query().items()), vec!["1", "2", "3", "4"]);
+        assert_eq!(ids(feed.query().tag("rust").items()), vec!["1", "2", "4"]);
+        assert_eq!(ids(feed.query().tag("rust").tag("audio").items()), vec!["1"]);
+        assert_eq!(ids(feed.query().since("2017-01-02T23:00:00Z").unwrap().items()), vec!["2", "3"]);
+        assert_eq!(ids(feed.query().author_name("alice").items()), vec!["1", "3", "4"]);
+        assert_eq!(ids(feed.query().author_name("bob").items()), vec!["2"]);
+        assert_eq!(ids(feed.query().tag("rust").limit(2).items()), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn query_invalid_since() {
+        match feed().query().since("last week") {
+            Err(Error::InvalidArgument { name, .. }) => assert_eq!(name, "since"),
+            other => panic!("expected an invalid argument error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn query_attachment_mime() {
+        let feed = feed();
+        assert_eq!(ids(feed.query().has_attachment_mime("audio/*").items()), vec!["1"]);
+        assert_eq!(ids(feed.query().has_attachment_mime("audio/mpeg").items()), vec!["1"]);
+        assert_eq!(ids(feed.query().has_attachment_mime("*/*").items()), vec!["1", "2"]);
+        assert_eq!(ids(feed.query().has_attachment_mime("video/*").items()), Vec::<&str>::new());
+        assert!(mime_matches("text/html", "text/html; charset=utf-8"));
+    }
+
+    #[test]
+    fn query_to_feed() {
+        let feed = feed();
+        let filtered = feed.query().tag("go").to_feed();
+        assert_eq!(filtered.title, "feed");
+        assert_eq!(filtered.author, feed.author);
+        assert_eq!(filtered.items, vec![feed.items[2].clone()]);
+    }
+}
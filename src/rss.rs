@@ -0,0 +1,573 @@
+//! RSS 2.0 export and import
+//!
+//! The feed becomes an RSS `channel`, its items `item`s and their first
+//! attachment an `enclosure`. Author names are written as `dc:creator`
+//! and `content_html` as `content:encoded`. Anything RSS has no place for
+//! is left out and listed in the returned `ConversionReport`.
+//!
+//! Importing goes the other way, producing a JSON Feed 1.1 feed. An item's
+//! `description` becomes its `content_html`, or its `summary` when the
+//! item also has `content:encoded`.
+
+use std::io::{Read, Write};
+
+use chrono::DateTime;
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+use errors::*;
+use convert::{self, ConversionReport};
+use dom::{self, Element};
+use feed::{Feed, Author, Attachment};
+use item::{Content, Item};
+use version::Version;
+
+const CONTENT_NS: &str = "http://purl.org/rss/1.0/modules/content/";
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+
+/// Writes a feed as an RSS 2.0 document
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::{Feed, Item};
+/// # fn main() {
+/// let feed = Feed::builder()
+///                 .title("my feed")
+///                 .home_page_url("https://example.com/")
+///                 .description("all about my feed")
+///                 .item(Item::builder().id("1").content_text("Hello, World!").build().unwrap())
+///                 .build()
+///                 .unwrap();
+/// let (rss, report) = jsonfeed::rss::to_string(&feed).unwrap();
+/// assert!(rss.contains("<guid isPermaLink=\"false\">1</guid>"));
+/// assert!(report.is_empty());
+/// # }
+/// ```
+pub fn to_string(feed: &Feed) -> Result<(String, ConversionReport)> {
+    let mut report = ConversionReport::default();
+    let mut w = EmitterConfig::new().perform_indent(true).create_writer(Vec::new());
+    write_feed(&mut w, feed, &mut report)?;
+    let rss = String::from_utf8(w.into_inner()).expect("xml-rs writes UTF-8");
+    Ok((rss, report))
+}
+
+fn write_feed<W: Write>(w: &mut EventWriter<W>, feed: &Feed, report: &mut ConversionReport) -> Result<()> {
+    w.write(XmlEvent::start_element("rss")
+                .attr("version", "2.0")
+                .ns("content", CONTENT_NS)
+                .ns("dc", DC_NS))?;
+    w.write(XmlEvent::start_element("channel"))?;
+
+    dom::write_text(w, "title", &feed.title)?;
+    match feed.home_page_url {
+        Some(ref url) => dom::write_text(w, "link", url)?,
+        None => {
+            dom::write_text(w, "link", "")?;
+            report.lossy("home_page_url", "RSS requires a channel link, written empty");
+        },
+    }
+    match feed.description {
+        Some(ref description) => dom::write_text(w, "description", description)?,
+        None => {
+            dom::write_text(w, "description", "")?;
+            report.lossy("description", "RSS requires a channel description, written empty");
+        },
+    }
+    if let Some(ref language) = feed.language {
+        dom::write_text(w, "language", language)?;
+    }
+    if let Some(ref icon) = feed.icon {
+        w.write(XmlEvent::start_element("image"))?;
+        dom::write_text(w, "url", icon)?;
+        dom::write_text(w, "title", &feed.title)?;
+        dom::write_text(w, "link", feed.home_page_url.as_ref().map_or("", |s| &s[..]))?;
+        w.write(XmlEvent::end_element())?;
+    }
+    creators(w, "", &feed.author, &feed.authors, report)?;
+
+    for (path, value) in &[("feed_url", feed.feed_url.is_some()),
+                           ("next_url", feed.next_url.is_some()),
+                           ("favicon", feed.favicon.is_some()),
+                           ("user_comment", feed.user_comment.is_some()),
+                           ("expired", feed.expired.is_some()),
+                           ("hubs", feed.hubs.is_some())] {
+        if *value {
+            report.unmapped(*path);
+        }
+    }
+    report.unmapped_extensions("", &feed.extensions);
+
+    for (i, item) in feed.items.iter().enumerate() {
+        write_item(w, &format!("items[{}].", i), item, report)?;
+    }
+
+    w.write(XmlEvent::end_element())?;
+    w.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+fn write_item<W: Write>(w: &mut EventWriter<W>,
+                        prefix: &str,
+                        item: &Item,
+                        report: &mut ConversionReport) -> Result<()>
+{
+    w.write(XmlEvent::start_element("item"))?;
+
+    if let Some(ref title) = item.title {
+        dom::write_text(w, "title", title)?;
+    }
+    if let Some(ref url) = item.url {
+        dom::write_text(w, "link", url)?;
+    }
+    let permalink = if item.url.as_ref() == Some(&item.id) { "true" } else { "false" };
+    w.write(XmlEvent::start_element("guid").attr("isPermaLink", permalink))?;
+    w.write(XmlEvent::characters(&item.id))?;
+    w.write(XmlEvent::end_element())?;
+
+    // RSS has one plain description, so content_text only fits when there
+    // is no summary to take its place
+    match (item.summary.as_ref(), item.content.text(), item.content.html()) {
+        (Some(summary), text, _) => {
+            dom::write_text(w, "description", summary)?;
+            if text.is_some() {
+                report.unmapped(format!("{}content_text", prefix));
+            }
+        },
+        (None, Some(text), _) => dom::write_text(w, "description", text)?,
+        (None, None, Some(html)) => dom::write_cdata(w, "description", html)?,
+        (None, None, None) => {},
+    }
+    if let Some(html) = item.content.html() {
+        dom::write_cdata(w, "content:encoded", html)?;
+    }
+    match (item.date_published_parsed(), &item.date_published) {
+        (Some(date), _) => dom::write_text(w, "pubDate", &date.to_rfc2822())?,
+        (None, Some(date)) => report.lossy(format!("{}date_published", prefix),
+                                           format!("invalid date '{}' left out", date)),
+        (None, None) => {},
+    }
+    creators(w, prefix, &item.author, &item.authors, report)?;
+    if let Some(ref tags) = item.tags {
+        for tag in tags {
+            dom::write_text(w, "category", tag)?;
+        }
+    }
+    if let Some(ref attachments) = item.attachments {
+        for (j, attachment) in attachments.iter().enumerate() {
+            let path = format!("{}attachments[{}]", prefix, j);
+            if j == 0 {
+                enclosure(w, &path, attachment, report)?;
+            } else {
+                // RSS allows a single enclosure per item
+                report.unmapped(path);
+            }
+        }
+    }
+
+    for (field, value) in &[("external_url", item.external_url.is_some()),
+                            ("image", item.image.is_some()),
+                            ("banner_image", item.banner_image.is_some()),
+                            ("date_modified", item.date_modified.is_some()),
+                            ("language", item.language.is_some())] {
+        if *value {
+            report.unmapped(format!("{}{}", prefix, field));
+        }
+    }
+    report.unmapped_extensions(prefix, &item.extensions);
+
+    w.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+fn enclosure<W: Write>(w: &mut EventWriter<W>,
+                       path: &str,
+                       attachment: &Attachment,
+                       report: &mut ConversionReport) -> Result<()>
+{
+    let length = match attachment.size_in_bytes {
+        Some(size) => size.to_string(),
+        None => {
+            report.lossy(format!("{}.size_in_bytes", path),
+                         "RSS requires an enclosure length, written as 0");
+            "0".to_string()
+        },
+    };
+    w.write(XmlEvent::start_element("enclosure")
+                .attr("url", &attachment.url)
+                .attr("length", &length)
+                .attr("type", &attachment.mime_type))?;
+    w.write(XmlEvent::end_element())?;
+
+    if attachment.title.is_some() {
+        report.unmapped(format!("{}.title", path));
+    }
+    if attachment.duration_in_seconds.is_some() {
+        report.unmapped(format!("{}.duration_in_seconds", path));
+    }
+    report.unmapped_extensions(&format!("{}.", path), &attachment.extensions);
+    Ok(())
+}
+
+/// Writes a `dc:creator` for each named author
+fn creators<W: Write>(w: &mut EventWriter<W>,
+                      prefix: &str,
+                      author: &Option<Author>,
+                      authors: &Option<Vec<Author>>,
+                      report: &mut ConversionReport) -> Result<()>
+{
+    let mut all = vec![];
+    if let Some(ref author) = *author {
+        all.push((format!("{}author", prefix), author));
+    }
+    if let Some(ref authors) = *authors {
+        for (i, author) in authors.iter().enumerate() {
+            all.push((format!("{}authors[{}]", prefix, i), author));
+        }
+    }
+
+    let mut written: Vec<&str> = vec![];
+    for (path, author) in all {
+        match author.name {
+            // `upgrade_to_1_1` can leave the same author in both fields
+            Some(ref name) if written.contains(&&name[..]) => {},
+            Some(ref name) => {
+                dom::write_text(w, "dc:creator", name)?;
+                written.push(name);
+            },
+            None => {},
+        }
+        if author.url.is_some() {
+            report.unmapped(format!("{}.url", path));
+        }
+        if author.avatar.is_some() {
+            report.unmapped(format!("{}.avatar", path));
+        }
+        report.unmapped_extensions(&format!("{}.", path), &author.extensions);
+    }
+    Ok(())
+}
+
+/// Reads an RSS 2.0 document into a feed
+///
+/// Items without a `guid` get their `link` as id or, failing that, a
+/// hash of their title, description and date, so importing the same
+/// document twice gives the same ids. Dates are converted from RFC 822
+/// to RFC 3339.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # fn main() {
+/// let rss = r#"<rss version="2.0"><channel>
+///     <title>my feed</title>
+///     <item><guid>1</guid><description>Hello, World!</description></item>
+/// </channel></rss>"#;
+/// let (feed, report) = jsonfeed::rss::from_reader(rss.as_bytes()).unwrap();
+/// assert_eq!(feed.items[0].id, "1");
+/// assert!(report.is_empty());
+/// # }
+/// ```
+pub fn from_reader<R: Read>(reader: R) -> Result<(Feed, ConversionReport)> {
+    let root = dom::parse(reader)?;
+    if !root.is(None, "rss") {
+        return Err(Error::invalid_value(format!("expected an RSS document, found <{}>", root.qualified), ""));
+    }
+    let channel = match root.child(None, "channel") {
+        Some(channel) => channel,
+        None => return Err(Error::missing_field("channel")),
+    };
+
+    let mut report = ConversionReport::default();
+    let title = channel.child_text(None, "title").unwrap_or_else(|| {
+        report.lossy("channel.title", "the channel has no title, written empty");
+        String::new()
+    });
+    let mut authors = vec![];
+    let mut items = vec![];
+    for child in &channel.children {
+        let known = match (child.namespace.as_ref().map(|s| &s[..]), &child.name[..]) {
+            (None, "item") => {
+                let path = format!("channel.item[{}]", items.len());
+                items.push(read_item(child, &path, &mut report));
+                true
+            },
+            (Some(DC_NS), "creator") => {
+                authors.extend(child.trimmed().map(|name| Author::builder().name(name).build()));
+                true
+            },
+            (None, name) => matches!(name, "title" | "link" | "description" | "language" | "image"),
+            _ => false,
+        };
+        if !known {
+            report.unmapped(format!("channel.{}", child.qualified));
+        }
+    }
+
+    let feed = Feed {
+        version: Version::V1_1,
+        title,
+        home_page_url: channel.child_text(None, "link"),
+        description: channel.child_text(None, "description"),
+        language: channel.child_text(None, "language"),
+        icon: channel.child(None, "image").and_then(|image| image.child_text(None, "url")),
+        authors: if authors.is_empty() { None } else { Some(authors) },
+        items,
+        ..Feed::default()
+    };
+    Ok((feed, report))
+}
+
+fn read_item(element: &Element, path: &str, report: &mut ConversionReport) -> Item {
+    let url = element.child_text(None, "link");
+    let description = element.child_text(None, "description");
+    let (summary, content) = match (description, element.child_text(Some(CONTENT_NS), "encoded")) {
+        (description, Some(html)) => (description, Content::Html(html)),
+        (Some(description), None) => (None, Content::Html(description)),
+        (None, None) => {
+            report.lossy(path, "the item has no description or content:encoded, content_text written empty");
+            (None, Content::Text(String::new()))
+        },
+    };
+
+    let id = match element.child_text(None, "guid") {
+        Some(guid) => guid,
+        None => match url {
+            Some(ref url) => {
+                report.lossy(format!("{}.guid", path), "the item has no guid, its link is used as id");
+                url.clone()
+            },
+            None => {
+                report.lossy(format!("{}.guid", path), "the item has no guid or link, a hash of it is used as id");
+                let text = |name| element.child(None, name).map_or("", |e| &e.text[..]);
+                convert::derived_id(&[text("title"), text("description"), text("pubDate")])
+            },
+        },
+    };
+
+    let mut date_published = None;
+    let mut authors = vec![];
+    let mut tags = vec![];
+    let mut attachments = vec![];
+    for child in &element.children {
+        let known = match (child.namespace.as_ref().map(|s| &s[..]), &child.name[..]) {
+            (None, "pubDate") => {
+                if let Some(date) = child.trimmed() {
+                    match DateTime::parse_from_rfc2822(&date) {
+                        Ok(date) => date_published = Some(date.to_rfc3339()),
+                        Err(e) => report.lossy(format!("{}.pubDate", path),
+                                               format!("invalid date '{}' left out: {}", date, e)),
+                    }
+                }
+                true
+            },
+            (Some(DC_NS), "creator") | (None, "author") => {
+                authors.extend(child.trimmed().map(|name| Author::builder().name(name).build()));
+                true
+            },
+            (None, "category") => {
+                tags.extend(child.trimmed());
+                true
+            },
+            (None, "enclosure") => {
+                let index = attachments.len();
+                match read_enclosure(child) {
+                    Some(attachment) => attachments.push(attachment),
+                    None => report.lossy(format!("{}.enclosure[{}]", path, index),
+                                         "the enclosure has no url, left out"),
+                }
+                true
+            },
+            (Some(CONTENT_NS), "encoded") => true,
+            (None, name) => matches!(name, "title" | "link" | "description" | "guid"),
+            _ => false,
+        };
+        if !known {
+            report.unmapped(format!("{}.{}", path, child.qualified));
+        }
+    }
+
+    Item {
+        id,
+        url,
+        title: element.child_text(None, "title"),
+        content,
+        summary,
+        date_published,
+        authors: if authors.is_empty() { None } else { Some(authors) },
+        tags: if tags.is_empty() { None } else { Some(tags) },
+        attachments: if attachments.is_empty() { None } else { Some(attachments) },
+        ..Item::default()
+    }
+}
+
+fn read_enclosure(element: &Element) -> Option<Attachment> {
+    let url = element.attr("url")?;
+    let mime_type = element.attr("type").unwrap_or("application/octet-stream");
+    let mut builder = Attachment::builder(url, mime_type);
+    // feeds commonly give a length of 0 when they don't know it
+    match element.attr("length").and_then(|s| s.trim().parse().ok()) {
+        Some(0) | None => {},
+        Some(size) => builder = builder.size_in_bytes(size),
+    }
+    Some(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use convert::ConversionWarning;
+    use feed::Hub;
+
+    fn feed() -> Feed {
+        ::from_str(r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "my feed",
+            "home_page_url": "https://example.com/",
+            "feed_url": "https://example.com/feed.json",
+            "authors": [{"name": "bob jones", "avatar": "https://example.com/bob.png"}],
+            "items": [
+                {
+                    "id": "https://example.com/1",
+                    "url": "https://example.com/1",
+                    "title": "first & best",
+                    "content_html": "<p>hello ]]> world</p>",
+                    "content_text": "hello world",
+                    "summary": "a greeting",
+                    "date_published": "2017-04-26T01:09:45+02:00",
+                    "tags": ["greetings"],
+                    "attachments": [
+                        {"url": "https://example.com/1.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 1024},
+                        {"url": "https://example.com/1.ogg", "mime_type": "audio/ogg"}
+                    ]
+                },
+                {
+                    "id": "2",
+                    "content_html": "<p>two</p>",
+                    "_ourapp": {"score": 3},
+                    "attachments": [{"url": "https://example.com/2.mp3", "mime_type": "audio/mpeg"}]
+                }
+            ]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn rss() {
+        let (rss, _) = to_string(&feed()).unwrap();
+        assert!(rss.contains(r#"<rss xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/" version="2.0">"#), "{}", rss);
+        assert!(rss.contains("<dc:creator>bob jones</dc:creator>"), "{}", rss);
+        assert!(rss.contains("<title>first &amp; best</title>"), "{}", rss);
+        assert!(rss.contains(r#"<guid isPermaLink="true">https://example.com/1</guid>"#), "{}", rss);
+        assert!(rss.contains("<description>a greeting</description>"), "{}", rss);
+        assert!(rss.contains("<content:encoded><![CDATA[<p>hello ]]]]><![CDATA[> world</p>]]></content:encoded>"), "{}", rss);
+        assert!(rss.contains("<pubDate>Wed, 26 Apr 2017 01:09:45 +0200</pubDate>"), "{}", rss);
+        assert!(rss.contains("<category>greetings</category>"), "{}", rss);
+        assert!(rss.contains(r#"<enclosure url="https://example.com/1.mp3" length="1024" type="audio/mpeg" />"#), "{}", rss);
+        assert!(rss.contains(r#"<guid isPermaLink="false">2</guid>"#), "{}", rss);
+        assert!(rss.contains("<description><![CDATA[<p>two</p>]]></description>"), "{}", rss);
+    }
+
+    #[test]
+    fn rss_report() {
+        let mut feed = feed();
+        feed.hubs = Some(vec![Hub::new("WebSub", "https://example.com/hub")]);
+        let (_, report) = to_string(&feed).unwrap();
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec![
+            "description",
+            "authors[0].avatar",
+            "feed_url",
+            "hubs",
+            "items[0].content_text",
+            "items[0].attachments[1]",
+            "items[1].attachments[0].size_in_bytes",
+            "items[1]._ourapp",
+        ]);
+        match report.warnings[0] {
+            ConversionWarning::Lossy { .. } => {},
+            ref other => panic!("expected a lossy conversion, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rss_import() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>my feed</title>
+    <link>https://example.com/</link>
+    <description>all about my feed</description>
+    <generator>hand</generator>
+    <image><url>https://example.com/icon.png</url></image>
+    <item>
+      <title>first</title>
+      <link>https://example.com/1</link>
+      <guid isPermaLink="true">https://example.com/1</guid>
+      <description>a greeting</description>
+      <content:encoded><![CDATA[<p>hello</p>]]></content:encoded>
+      <pubDate>Wed, 26 Apr 2017 01:09:45 +0200</pubDate>
+      <dc:creator>bob jones</dc:creator>
+      <category>greetings</category>
+      <enclosure url="https://example.com/1.mp3" length="1024" type="audio/mpeg"/>
+      <comments>https://example.com/1#comments</comments>
+    </item>
+    <item>
+      <link>https://example.com/2</link>
+      <description>&lt;p&gt;two&lt;/p&gt;</description>
+    </item>
+    <item>
+      <title>three</title>
+      <pubDate>yesterday</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+        let (feed, report) = from_reader(rss.as_bytes()).unwrap();
+        assert_eq!(feed.version, Version::V1_1);
+        assert_eq!(feed.title, "my feed");
+        assert_eq!(feed.icon, Some("https://example.com/icon.png".to_string()));
+
+        let first = &feed.items[0];
+        assert_eq!(first.id, "https://example.com/1");
+        assert_eq!(first.summary, Some("a greeting".to_string()));
+        assert_eq!(first.content, Content::Html("<p>hello</p>".to_string()));
+        assert_eq!(first.date_published.as_ref().unwrap(), "2017-04-26T01:09:45+02:00");
+        assert_eq!(first.authors, Some(vec![Author::builder().name("bob jones").build()]));
+        assert_eq!(first.tags, Some(vec!["greetings".to_string()]));
+        assert_eq!(first.attachments.as_ref().unwrap()[0].size_in_bytes(), Some(1024));
+
+        assert_eq!(feed.items[1].id, "https://example.com/2");
+        assert_eq!(feed.items[1].content, Content::Html("<p>two</p>".to_string()));
+        assert_eq!(feed.items[2].id.len(), 16);
+        assert_eq!(feed.items[2].id, from_reader(rss.as_bytes()).unwrap().0.items[2].id);
+
+        let paths: Vec<&str> = report.warnings.iter().map(|w| w.path()).collect();
+        assert_eq!(paths, vec![
+            "channel.generator",
+            "channel.item[0].comments",
+            "channel.item[1].guid",
+            "channel.item[2]",
+            "channel.item[2].guid",
+            "channel.item[2].pubDate",
+        ]);
+    }
+
+    #[test]
+    fn rss_round_trip() {
+        let (rss, _) = to_string(&feed()).unwrap();
+        let (imported, _) = from_reader(rss.as_bytes()).unwrap();
+        let original = feed();
+        assert_eq!(imported.title, original.title);
+        assert_eq!(imported.authors, Some(vec![Author::builder().name("bob jones").build()]));
+        for (imported, original) in imported.items.iter().zip(original.items.iter()) {
+            assert_eq!(imported.id, original.id);
+            assert_eq!(imported.content.html(), original.content.html());
+            assert_eq!(imported.date_published, original.date_published);
+        }
+    }
+
+    #[test]
+    fn rss_import_errors() {
+        assert!(from_reader("<feed/>".as_bytes()).is_err());
+        match from_reader(r#"<rss version="2.0"/>"#.as_bytes()) {
+            Err(Error::MissingField { field, .. }) => assert_eq!(field, "channel"),
+            other => panic!("expected a missing field error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Reading and writing a feed one item at a time
+//!
+//! `from_reader` builds the whole `Feed` in memory. For very large feeds,
+//! `items_from_reader` instead scans the document incrementally and parses
+//! each item on its own, so memory use is bounded by the largest item
+//! rather than the whole feed. `FeedWriter` does the same for writing.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use serde_json::{self, Map, Value};
+use serde_path_to_error;
+
+use errors::*;
+use feed::Feed;
+use item::Item;
+
+/// Something read from a feed by `items_from_reader`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The feed's attributes, with no items
+    ///
+    /// This comes before the first item, if the attributes before `items`
+    /// make up a valid feed. It comes again after the last item if more
+    /// attributes follow `items`, or if they were needed for a valid
+    /// feed. The last `Metadata` event has all of them.
+    Metadata(Feed),
+    /// The next item in the feed
+    Item(Item),
+}
+
+/// Reads a feed from a reader, one item at a time
+///
+/// An item that can't be parsed gives an error and is skipped, and
+/// reading carries on with the next one. Any other error ends the stream.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::stream::{self, Event};
+/// # fn main() {
+/// let json = r#"{"version": "https://jsonfeed.org/version/1", "title": "my feed",
+///                "items": [{"id": "1", "content_text": "one"}, {"id": "2", "content_text": "two"}]}"#;
+/// let mut ids = vec![];
+/// for event in stream::items_from_reader(json.as_bytes()) {
+///     match event.unwrap() {
+///         Event::Metadata(feed) => assert_eq!(feed.title, "my feed"),
+///         Event::Item(item) => ids.push(item.id),
+///     }
+/// }
+/// assert_eq!(ids, vec!["1", "2"]);
+/// # }
+/// ```
+pub fn items_from_reader<R: Read>(reader: R) -> ItemStream<R> {
+    ItemStream {
+        scanner: Scanner { reader: BufReader::new(reader), line: 1, column: 1 },
+        state: State::Start,
+        metadata: Map::new(),
+        metadata_sent: false,
+        keys_after_items: false,
+        index: 0,
+    }
+}
+
+/// An iterator over the events of a feed, returned by `items_from_reader`
+pub struct ItemStream<R> {
+    scanner: Scanner<R>,
+    state: State,
+    metadata: Map<String, Value>,
+    metadata_sent: bool,
+    keys_after_items: bool,
+    index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    /// In the top-level object, after `{` or after a value
+    Keys { first: bool },
+    /// In the items array, after `[` or after an item
+    Items { first: bool },
+    Done,
+}
+
+impl<R: Read> ItemStream<R> {
+    /// Reads up to the next event. Errors in the outer `Result` end the
+    /// stream, while errors in the inner one only skip an item.
+    fn step(&mut self) -> Result<Option<Result<Event>>> {
+        loop {
+            match self.state {
+                State::Start => {
+                    self.scanner.skip_whitespace()?;
+                    self.scanner.expect(b'{', "")?;
+                    self.state = State::Keys { first: true };
+                },
+                State::Keys { first } => {
+                    self.scanner.skip_whitespace()?;
+                    if self.scanner.peek()? == Some(b'}') {
+                        self.scanner.next()?;
+                        self.state = State::Done;
+                        self.scanner.skip_whitespace()?;
+                        if self.scanner.peek()?.is_some() {
+                            return Err(self.scanner.error("trailing characters", ""));
+                        }
+                        if self.metadata_sent && !self.keys_after_items {
+                            return Ok(None);
+                        }
+                        return self.metadata().map(|event| Some(Ok(event)));
+                    }
+                    if !first {
+                        self.scanner.expect(b',', "")?;
+                        self.scanner.skip_whitespace()?;
+                    }
+                    let key = self.scanner.key()?;
+                    self.scanner.skip_whitespace()?;
+                    if key == "items" {
+                        self.scanner.expect(b'[', "items")?;
+                        self.metadata.insert(key, Value::Array(vec![]));
+                        self.state = State::Items { first: true };
+                        if !self.metadata_sent {
+                            if let Ok(event) = self.metadata() {
+                                return Ok(Some(Ok(event)));
+                            }
+                        }
+                    } else {
+                        let mut buf = vec![];
+                        self.scanner.value(&mut buf, &key)?;
+                        let value = serde_json::from_slice(&buf)?;
+                        self.keys_after_items = self.metadata.contains_key("items");
+                        self.metadata.insert(key, value);
+                        self.state = State::Keys { first: false };
+                    }
+                },
+                State::Items { first } => {
+                    self.scanner.skip_whitespace()?;
+                    if self.scanner.peek()? == Some(b']') {
+                        self.scanner.next()?;
+                        self.state = State::Keys { first: false };
+                        continue;
+                    }
+                    let path = format!("items[{}]", self.index);
+                    if !first {
+                        self.scanner.expect(b',', &path)?;
+                        self.scanner.skip_whitespace()?;
+                    }
+                    self.state = State::Items { first: false };
+                    self.index += 1;
+
+                    let (line, column) = (self.scanner.line, self.scanner.column);
+                    let mut buf = vec![];
+                    self.scanner.value(&mut buf, &path)?;
+                    let event = item(&buf)
+                        .map(Event::Item)
+                        .map_err(|e| e.nested(&path, line, column));
+                    return Ok(Some(event));
+                },
+                State::Done => return Ok(None),
+            }
+        }
+    }
+
+    fn metadata(&mut self) -> Result<Event> {
+        let feed = ::from_value(Value::Object(self.metadata.clone()))?;
+        self.metadata_sent = true;
+        self.keys_after_items = false;
+        Ok(Event::Metadata(feed))
+    }
+}
+
+/// Parses an item on its own, with error paths relative to the item
+fn item(buf: &[u8]) -> Result<Item> {
+    Ok(serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_slice(buf))?)
+}
+
+impl<R: Read> Iterator for ItemStream<R> {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Result<Event>> {
+        match self.step() {
+            Ok(event) => event,
+            Err(e) => {
+                self.state = State::Done;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+/// Reads the raw text of JSON values, without parsing them
+struct Scanner<R> {
+    reader: BufReader<R>,
+    line: usize,
+    column: usize,
+}
+
+impl<R: Read> Scanner<R> {
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().cloned())
+    }
+
+    fn next(&mut self) -> Result<Option<u8>> {
+        let byte = self.peek()?;
+        if let Some(byte) = byte {
+            self.reader.consume(1);
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        Ok(byte)
+    }
+
+    fn next_or_eof(&mut self, path: &str) -> Result<u8> {
+        match self.next()? {
+            Some(byte) => Ok(byte),
+            None => Err(self.error("unexpected end of input", path)),
+        }
+    }
+
+    fn error(&self, message: &str, path: &str) -> Error {
+        Error::InvalidValue {
+            message: message.to_string(),
+            location: Location { path: path.to_string(), line: Some(self.line), column: Some(self.column) },
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<()> {
+        while let Some(byte) = self.peek()? {
+            match byte {
+                b' ' | b'\t' | b'\n' | b'\r' => { self.next()?; },
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn expect(&mut self, expected: u8, path: &str) -> Result<()> {
+        match self.peek()? {
+            Some(byte) if byte == expected => {
+                self.next()?;
+                Ok(())
+            },
+            _ => Err(self.error(&format!("expected `{}`", expected as char), path)),
+        }
+    }
+
+    /// Reads an object key and the `:` after it
+    fn key(&mut self) -> Result<String> {
+        if self.peek()? != Some(b'"') {
+            return Err(self.error("expected a key", ""));
+        }
+        let mut buf = vec![];
+        self.value(&mut buf, "")?;
+        let key: String = serde_json::from_slice(&buf)?;
+        self.skip_whitespace()?;
+        self.expect(b':', &key)?;
+        Ok(key)
+    }
+
+    /// Appends the raw text of the next value to `buf`
+    fn value(&mut self, buf: &mut Vec<u8>, path: &str) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            let byte = match self.peek()? {
+                // scalars end at the first character that can't be in them
+                Some(b',') | Some(b'}') | Some(b']') | Some(b' ') |
+                Some(b'\t') | Some(b'\n') | Some(b'\r') | None if depth == 0 => {
+                    return if buf.is_empty() { Err(self.error("expected a value", path)) } else { Ok(()) };
+                },
+                _ => self.next_or_eof(path)?,
+            };
+            buf.push(byte);
+            match byte {
+                b'"' => {
+                    loop {
+                        let byte = self.next_or_eof(path)?;
+                        buf.push(byte);
+                        match byte {
+                            b'\\' => buf.push(self.next_or_eof(path)?),
+                            b'"' => break,
+                            _ => {},
+                        }
+                    }
+                },
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => depth = depth.saturating_sub(1),
+                _ => {},
+            }
+            if depth == 0 && (byte == b'"' || byte == b'}' || byte == b']') {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Writes a feed one item at a time
+///
+/// The output is byte for byte what `to_writer`, or `to_writer_pretty`,
+/// would write for the same feed with all the items in it. The document
+/// is only complete once `finish` is called.
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::{Feed, FeedWriter, Item};
+/// # fn main() {
+/// let feed = Feed::builder().title("my feed").build().unwrap();
+/// let mut writer = FeedWriter::new(Vec::new(), &feed).unwrap();
+/// for i in 0..3 {
+///     let item = Item::builder().id(i.to_string()).content_text("hi").build().unwrap();
+///     writer.write_item(&item).unwrap();
+/// }
+/// let json = writer.finish().unwrap();
+/// assert_eq!(jsonfeed::from_slice(&json).unwrap().items.len(), 3);
+/// # }
+/// ```
+pub struct FeedWriter<W: Write> {
+    writer: W,
+    pretty: bool,
+    /// Everything after the items, starting with the `]`
+    footer: Vec<u8>,
+    items: usize,
+}
+
+impl<W: Write> FeedWriter<W> {
+    /// Writes the start of a compact feed, followed by any items already
+    /// in `feed`
+    pub fn new(writer: W, feed: &Feed) -> Result<FeedWriter<W>> {
+        FeedWriter::start(writer, feed, false)
+    }
+
+    /// Writes the start of a pretty-printed feed, followed by any items
+    /// already in `feed`
+    pub fn pretty(writer: W, feed: &Feed) -> Result<FeedWriter<W>> {
+        FeedWriter::start(writer, feed, true)
+    }
+
+    fn start(mut writer: W, feed: &Feed, pretty: bool) -> Result<FeedWriter<W>> {
+        ::check(feed)?;
+        let (json, marker): (Vec<u8>, &[u8]) = if pretty {
+            (serde_json::to_vec_pretty(&feed.metadata())?, b"\"items\": []")
+        } else {
+            (serde_json::to_vec(&feed.metadata())?, b"\"items\":[]")
+        };
+        // `items` is serialized before the extensions, the only other
+        // place the marker could appear outside a string
+        let at = json.windows(marker.len())
+            .position(|window| window == marker)
+            .expect("a feed always has items") + marker.len() - 1;
+        writer.write_all(&json[..at])?;
+
+        let mut feed_writer = FeedWriter {
+            writer,
+            pretty,
+            footer: json[at..].to_vec(),
+            items: 0,
+        };
+        for item in &feed.items {
+            feed_writer.write_item(item)?;
+        }
+        Ok(feed_writer)
+    }
+
+    /// Writes the next item
+    pub fn write_item(&mut self, item: &Item) -> Result<()> {
+        if self.pretty {
+            // items are nested two levels deep, so indent them by four
+            // spaces. Newlines inside strings are escaped, so every
+            // newline in the output is the formatter's.
+            let json = serde_json::to_string_pretty(item)?;
+            let separator = if self.items == 0 { "\n    " } else { ",\n    " };
+            self.writer.write_all(separator.as_bytes())?;
+            self.writer.write_all(json.replace('\n', "\n    ").as_bytes())?;
+        } else {
+            if self.items > 0 {
+                self.writer.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut self.writer, item)?;
+        }
+        self.items += 1;
+        Ok(())
+    }
+
+    /// Writes the end of the feed, returning the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        if self.pretty && self.items > 0 {
+            self.writer.write_all(b"\n  ")?;
+        }
+        self.writer.write_all(&self.footer)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(json: &str) -> Vec<Result<Event>> {
+        items_from_reader(json.as_bytes()).collect()
+    }
+
+    fn describe(events: &[Result<Event>]) -> Vec<String> {
+        events.iter().map(|event| match *event {
+            Ok(Event::Metadata(ref feed)) => format!("metadata {}", feed.title),
+            Ok(Event::Item(ref item)) => format!("item {}", item.id),
+            Err(ref e) => format!("error {}", e.path().unwrap_or("")),
+        }).collect()
+    }
+
+    #[test]
+    fn metadata_first() {
+        let json = r#"{"version": "https://jsonfeed.org/version/1", "title": "my \"feed\" [1]",
+                       "items": [{"id": "1", "content_text": "{]"}, {"id": "2", "content_html": "<p>2</p>", "tags": ["a", "b"]}]}"#;
+        assert_eq!(describe(&events(json)), vec!["metadata my \"feed\" [1]", "item 1", "item 2"]);
+    }
+
+    #[test]
+    fn metadata_after_items() {
+        let json = r#"{"items": [{"id": "1", "content_text": ""}], "version": "https://jsonfeed.org/version/1", "title": "my feed"}"#;
+        assert_eq!(describe(&events(json)), vec!["item 1", "metadata my feed"]);
+
+        let json = r#"{"title": "my feed", "version": "https://jsonfeed.org/version/1", "items": [], "home_page_url": "https://example.com/"}"#;
+        let events = events(json);
+        assert_eq!(describe(&events), vec!["metadata my feed", "metadata my feed"]);
+        match events[1] {
+            Ok(Event::Metadata(ref feed)) => assert_eq!(feed.home_page_url, Some("https://example.com/".to_string())),
+            ref other => panic!("expected metadata, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_items_are_skipped() {
+        let json = "{\"version\": \"https://jsonfeed.org/version/1\", \"title\": \"\", \"items\": [\n{\"id\": \"1\", \"content_text\": \"\"},\n  {\"content_text\": \"\"},\n{\"id\": 3, \"content_text\": \"\"}, {\"id\": \"4\", \"content_text\": \"\"}]}";
+        let events = events(json);
+        assert_eq!(describe(&events), vec!["metadata ", "item 1", "error items[1].id", "error items[2].id", "item 4"]);
+        match events[2] {
+            Err(ref e) => {
+                assert_eq!(e.line(), Some(3));
+                assert_eq!(e.column(), Some(22));
+            },
+            ref other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn syntax_errors_end_the_stream() {
+        let json = r#"{"version": "https://jsonfeed.org/version/1", "title": "", "items": [{"id": "1", "content_text": ""} {"id": "2"}]}"#;
+        assert_eq!(describe(&events(json)), vec!["metadata ", "item 1", "error items[1]"]);
+        assert_eq!(describe(&events(r#"{"title": "#)), vec!["error title"]);
+        assert_eq!(describe(&events(r#"[]"#)), vec!["error "]);
+    }
+
+    #[test]
+    fn missing_metadata() {
+        let json = r#"{"items": [{"id": "1", "content_text": ""}]}"#;
+        assert_eq!(describe(&events(json)), vec!["item 1", "error version"]);
+    }
+
+    fn feed() -> Feed {
+        ::from_str(r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "my \"items\":[] feed",
+            "home_page_url": "https://example.com/",
+            "authors": [{"name": "bob", "_x": {"items": []}}],
+            "_ext": {"items": [], "nested": {"a": [1, 2]}},
+            "items": [
+                {"id": "1", "content_text": "line one\nline two", "tags": ["a", "b"],
+                 "attachments": [{"url": "https://example.com/a.mp3", "mime_type": "audio/mpeg"}]},
+                {"id": "2", "content_html": "<p>two</p>", "_ourapp": {"items": []}}
+            ]
+        }"#).unwrap()
+    }
+
+    fn write(feed: &Feed, pretty: bool) -> Vec<u8> {
+        let header = Feed { items: vec![], ..feed.clone() };
+        let mut writer = if pretty {
+            FeedWriter::pretty(Vec::new(), &header).unwrap()
+        } else {
+            FeedWriter::new(Vec::new(), &header).unwrap()
+        };
+        for item in &feed.items {
+            writer.write_item(item).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn writer_matches_to_writer() {
+        let feed = feed();
+        assert_eq!(String::from_utf8(write(&feed, false)).unwrap(), ::to_string(&feed).unwrap());
+        assert_eq!(String::from_utf8(write(&feed, true)).unwrap(), ::to_string_pretty(&feed).unwrap());
+
+        let empty = Feed { items: vec![], ..feed };
+        assert_eq!(String::from_utf8(write(&empty, false)).unwrap(), ::to_string(&empty).unwrap());
+        assert_eq!(String::from_utf8(write(&empty, true)).unwrap(), ::to_string_pretty(&empty).unwrap());
+    }
+
+    #[test]
+    fn writer_writes_feed_items() {
+        let feed = feed();
+        let json = FeedWriter::pretty(Vec::new(), &feed).unwrap().finish().unwrap();
+        assert_eq!(String::from_utf8(json).unwrap(), ::to_string_pretty(&feed).unwrap());
+    }
+}
@@ -0,0 +1,312 @@
+//! The URL-bearing attributes of a feed
+//!
+//! Every URL is addressed by its path in the feed, e.g.
+//! `items[17].attachments[0].url`. With the `url` feature enabled,
+//! URLs can be parsed into `url::Url` values on request. Parsing and
+//! serializing never look at them.
+
+#[cfg(feature = "url")]
+use url::{self, Url};
+
+#[cfg(feature = "url")]
+use errors::*;
+#[cfg(feature = "url")]
+use feed::{Attachment, Hub};
+use feed::{Feed, Author};
+use item::Item;
+
+/// Defines a method parsing each listed optional URL field
+#[cfg(feature = "url")]
+macro_rules! parsed_url_fields {
+    ($type_:ty, $($method:ident => $field:ident),*) => {
+        impl $type_ {
+            $(
+                #[doc = concat!("Parses `", stringify!($field), "`, if there is one")]
+                pub fn $method(&self) -> Option<::std::result::Result<Url, url::ParseError>> {
+                    self.$field.as_ref().map(|s| Url::parse(s))
+                }
+            )*
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+parsed_url_fields!(Feed,
+                   home_page_url_parsed => home_page_url,
+                   feed_url_parsed => feed_url,
+                   next_url_parsed => next_url,
+                   icon_parsed => icon,
+                   favicon_parsed => favicon);
+
+#[cfg(feature = "url")]
+parsed_url_fields!(Item,
+                   url_parsed => url,
+                   external_url_parsed => external_url,
+                   image_parsed => image,
+                   banner_image_parsed => banner_image);
+
+#[cfg(feature = "url")]
+parsed_url_fields!(Author,
+                   url_parsed => url,
+                   avatar_parsed => avatar);
+
+#[cfg(feature = "url")]
+impl Attachment {
+    /// Parses `url`
+    pub fn url_parsed(&self) -> ::std::result::Result<Url, url::ParseError> {
+        Url::parse(&self.url)
+    }
+}
+
+#[cfg(feature = "url")]
+impl Hub {
+    /// Parses `url`
+    pub fn url_parsed(&self) -> ::std::result::Result<Url, url::ParseError> {
+        Url::parse(&self.url)
+    }
+}
+
+impl Feed {
+    /// Returns every URL in the feed, along with its path
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::Feed;
+    /// # fn main() {
+    /// let feed = Feed { feed_url: Some("https://example.com/feed.json".into()), ..Feed::default() };
+    /// assert_eq!(feed.urls(), vec![("feed_url".to_string(), "https://example.com/feed.json")]);
+    /// # }
+    /// ```
+    pub fn urls(&self) -> Vec<(String, &str)> {
+        let mut urls = vec![];
+        push(&mut urls, "home_page_url".to_string(), &self.home_page_url);
+        push(&mut urls, "feed_url".to_string(), &self.feed_url);
+        push(&mut urls, "next_url".to_string(), &self.next_url);
+        push(&mut urls, "icon".to_string(), &self.icon);
+        push(&mut urls, "favicon".to_string(), &self.favicon);
+        author_urls(&mut urls, "", &self.author, &self.authors);
+        if let Some(ref hubs) = self.hubs {
+            for (i, hub) in hubs.iter().enumerate() {
+                urls.push((format!("hubs[{}].url", i), &hub.url[..]));
+            }
+        }
+        for (i, item) in self.items.iter().enumerate() {
+            item_urls(&mut urls, &format!("items[{}].", i), item);
+        }
+        urls
+    }
+
+    /// Parses every URL in the feed
+    ///
+    /// Fails on the first URL that doesn't parse, naming its path.
+    #[cfg(feature = "url")]
+    pub fn parsed_urls(&self) -> Result<Vec<(String, Url)>> {
+        self.urls().into_iter().map(|(path, s)| parse(path, s)).collect()
+    }
+
+    /// Drops every URL that doesn't parse, returning the path and parse
+    /// error of each
+    ///
+    /// Attachments and hubs, whose `url` is required, are dropped entirely.
+    #[cfg(feature = "url")]
+    pub fn drop_invalid_urls(&mut self) -> Vec<(String, String)> {
+        remove_invalid(self)
+    }
+}
+
+#[cfg(feature = "url")]
+fn parse(path: String, s: &str) -> Result<(String, Url)> {
+    match Url::parse(s) {
+        Ok(url) => Ok((path, url)),
+        Err(e) => Err(Error::invalid_value(invalid_url(s, e), path)),
+    }
+}
+
+#[cfg(feature = "url")]
+fn invalid_url(s: &str, e: url::ParseError) -> String {
+    format!("invalid URL '{}': {}", s, e)
+}
+
+fn push<'a>(urls: &mut Vec<(String, &'a str)>, path: String, url: &'a Option<String>) {
+    if let Some(ref url) = *url {
+        urls.push((path, url));
+    }
+}
+
+fn author_urls<'a>(urls: &mut Vec<(String, &'a str)>,
+                   prefix: &str,
+                   author: &'a Option<Author>,
+                   authors: &'a Option<Vec<Author>>)
+{
+    if let Some(ref author) = *author {
+        push(urls, format!("{}author.url", prefix), &author.url);
+        push(urls, format!("{}author.avatar", prefix), &author.avatar);
+    }
+    if let Some(ref authors) = *authors {
+        for (i, author) in authors.iter().enumerate() {
+            push(urls, format!("{}authors[{}].url", prefix, i), &author.url);
+            push(urls, format!("{}authors[{}].avatar", prefix, i), &author.avatar);
+        }
+    }
+}
+
+fn item_urls<'a>(urls: &mut Vec<(String, &'a str)>, prefix: &str, item: &'a Item) {
+    push(urls, format!("{}url", prefix), &item.url);
+    push(urls, format!("{}external_url", prefix), &item.external_url);
+    push(urls, format!("{}image", prefix), &item.image);
+    push(urls, format!("{}banner_image", prefix), &item.banner_image);
+    author_urls(urls, prefix, &item.author, &item.authors);
+    if let Some(ref attachments) = item.attachments {
+        for (i, attachment) in attachments.iter().enumerate() {
+            urls.push((format!("{}attachments[{}].url", prefix, i), &attachment.url[..]));
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+fn remove_invalid(feed: &mut Feed) -> Vec<(String, String)> {
+    let mut removed = vec![];
+    check_opt(&mut removed, "home_page_url".to_string(), &mut feed.home_page_url);
+    check_opt(&mut removed, "feed_url".to_string(), &mut feed.feed_url);
+    check_opt(&mut removed, "next_url".to_string(), &mut feed.next_url);
+    check_opt(&mut removed, "icon".to_string(), &mut feed.icon);
+    check_opt(&mut removed, "favicon".to_string(), &mut feed.favicon);
+    check_authors(&mut removed, "", &mut feed.author, &mut feed.authors);
+    if let Some(ref mut hubs) = feed.hubs {
+        let mut i = 0;
+        hubs.retain(|hub| {
+            let keep = check_required(&mut removed, format!("hubs[{}].url", i), &hub.url);
+            i += 1;
+            keep
+        });
+    }
+    for (i, item) in feed.items.iter_mut().enumerate() {
+        let prefix = format!("items[{}].", i);
+        check_opt(&mut removed, format!("{}url", prefix), &mut item.url);
+        check_opt(&mut removed, format!("{}external_url", prefix), &mut item.external_url);
+        check_opt(&mut removed, format!("{}image", prefix), &mut item.image);
+        check_opt(&mut removed, format!("{}banner_image", prefix), &mut item.banner_image);
+        check_authors(&mut removed, &prefix, &mut item.author, &mut item.authors);
+        if let Some(ref mut attachments) = item.attachments {
+            let mut j = 0;
+            attachments.retain(|attachment| {
+                let path = format!("{}attachments[{}].url", prefix, j);
+                let keep = check_required(&mut removed, path, &attachment.url);
+                j += 1;
+                keep
+            });
+        }
+    }
+    removed
+}
+
+#[cfg(feature = "url")]
+fn check_opt(removed: &mut Vec<(String, String)>, path: String, url: &mut Option<String>) {
+    let invalid = match *url {
+        Some(ref s) => Url::parse(s).err().map(|e| invalid_url(s, e)),
+        None => None,
+    };
+    if let Some(message) = invalid {
+        *url = None;
+        removed.push((path, message));
+    }
+}
+
+#[cfg(feature = "url")]
+fn check_required(removed: &mut Vec<(String, String)>, path: String, url: &str) -> bool {
+    match Url::parse(url) {
+        Ok(_) => true,
+        Err(e) => {
+            removed.push((path, invalid_url(url, e)));
+            false
+        },
+    }
+}
+
+#[cfg(feature = "url")]
+fn check_authors(removed: &mut Vec<(String, String)>,
+                 prefix: &str,
+                 author: &mut Option<Author>,
+                 authors: &mut Option<Vec<Author>>)
+{
+    if let Some(ref mut author) = *author {
+        check_opt(removed, format!("{}author.url", prefix), &mut author.url);
+        check_opt(removed, format!("{}author.avatar", prefix), &mut author.avatar);
+    }
+    if let Some(ref mut authors) = *authors {
+        for (i, author) in authors.iter_mut().enumerate() {
+            check_opt(removed, format!("{}authors[{}].url", prefix, i), &mut author.url);
+            check_opt(removed, format!("{}authors[{}].avatar", prefix, i), &mut author.avatar);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::default::Default;
+    use item::Content;
+
+    fn feed() -> Feed {
+        Feed {
+            home_page_url: Some("https://example.com/".to_string()),
+            author: Some(Author::builder().avatar("https://example.com/me.png").build()),
+            items: vec![
+                Item {
+                    id: "1".to_string(),
+                    url: Some("/relative".to_string()),
+                    content: Content::Text("".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            feed().urls(),
+            vec![
+                ("home_page_url".to_string(), "https://example.com/"),
+                ("author.avatar".to_string(), "https://example.com/me.png"),
+                ("items[0].url".to_string(), "/relative"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "url")]
+    fn parsed_urls() {
+        match feed().parsed_urls() {
+            Err(Error::InvalidValue { location, .. }) => assert_eq!(location.path, "items[0].url"),
+            other => panic!("expected an invalid url error, got {:?}", other),
+        }
+
+        let mut feed = feed();
+        feed.hubs = Some(vec![Hub::new("WebSub", "hub")]);
+        feed.items[0].attachments = Some(vec![Attachment::builder("a.mp3", "audio/mpeg").build()]);
+        let removed = feed.drop_invalid_urls();
+        assert_eq!(removed, vec![
+            ("hubs[0].url".to_string(), "invalid URL 'hub': relative URL without a base".to_string()),
+            ("items[0].url".to_string(), "invalid URL '/relative': relative URL without a base".to_string()),
+            ("items[0].attachments[0].url".to_string(), "invalid URL 'a.mp3': relative URL without a base".to_string()),
+        ]);
+        assert_eq!(feed.items[0].url, None);
+        assert_eq!(feed.hubs, Some(vec![]));
+        assert_eq!(feed.items[0].attachments, Some(vec![]));
+        let urls = feed.parsed_urls().unwrap();
+        assert_eq!(urls[0].1, Url::parse("https://example.com/").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "url")]
+    fn parsed_url_fields() {
+        let feed = feed();
+        assert_eq!(feed.home_page_url_parsed(), Some(Ok(Url::parse("https://example.com/").unwrap())));
+        assert_eq!(feed.feed_url_parsed(), None);
+        assert_eq!(feed.items[0].url_parsed(), Some(Err(url::ParseError::RelativeUrlWithoutBase)));
+        let avatar = feed.author.as_ref().unwrap().avatar_parsed();
+        assert_eq!(avatar.unwrap().unwrap().path(), "/me.png");
+    }
+}
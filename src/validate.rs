@@ -0,0 +1,291 @@
+//! Spec-conformance checks that serde can't enforce
+
+use std::fmt;
+use std::collections::HashMap;
+
+#[cfg(feature = "url")]
+use url::Url;
+
+use date;
+use feed::Feed;
+use item::Content;
+
+/// How serious a `Diagnostic` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The feed is usable, but something looks wrong
+    Warning,
+    /// The feed doesn't conform to the spec
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A problem found by `Feed::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A JSON pointer to the offending value, e.g. `/items/3/id`
+    pub path: String,
+    /// A stable identifier for the rule, e.g. `duplicate-id`
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error<P: Into<String>, M: Into<String>>(path: P, code: &'static str, message: M) -> Diagnostic {
+        Diagnostic { severity: Severity::Error, path: path.into(), code, message: message.into() }
+    }
+
+    fn warning<P: Into<String>, M: Into<String>>(path: P, code: &'static str, message: M) -> Diagnostic {
+        Diagnostic { severity: Severity::Warning, path: path.into(), code, message: message.into() }
+    }
+
+    /// Returns true if the diagnostic has `Severity::Error`
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}] {}: {}", self.severity, self.code, self.path, self.message)
+    }
+}
+
+/// Longest `duration_in_seconds` that doesn't look like a mistake: a week
+const MAX_DURATION: u64 = 7 * 24 * 60 * 60;
+
+impl Feed {
+    /// Checks the feed against the rules of the spec that can't be
+    /// expressed in its types
+    ///
+    /// ```rust
+    /// # extern crate jsonfeed;
+    /// # use jsonfeed::{Feed, Severity};
+    /// # fn main() {
+    /// let diagnostics = Feed::default().validate();
+    /// assert_eq!(diagnostics[0].code, "empty-title");
+    /// assert_eq!(diagnostics[0].severity, Severity::Error);
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+
+        if !self.version.is_known() {
+            diagnostics.push(Diagnostic::error("/version", "unsupported-version",
+                format!("unsupported JSON Feed version '{}'", self.version)));
+        }
+        if self.title.trim().is_empty() {
+            diagnostics.push(Diagnostic::error("/title", "empty-title", "the feed title is empty"));
+        }
+        if self.next_url.is_some() && self.next_url == self.feed_url {
+            diagnostics.push(Diagnostic::error("/next_url", "next-url-is-feed-url",
+                "next_url points back at feed_url"));
+        }
+
+        for (path, url) in self.urls() {
+            let path = pointer(&path);
+            if let Some(message) = invalid_url(url) {
+                diagnostics.push(Diagnostic::error(path, "invalid-url", message));
+            } else if !has_scheme(url) {
+                diagnostics.push(Diagnostic::error(path, "relative-url",
+                    format!("'{}' is not an absolute URL", url)));
+            }
+        }
+
+        let mut ids = HashMap::new();
+        let mut previous: Option<(usize, i64)> = None;
+        for (i, item) in self.items.iter().enumerate() {
+            if item.id.is_empty() {
+                diagnostics.push(Diagnostic::error(format!("/items/{}/id", i), "empty-id",
+                    "the item id is empty"));
+            } else if let Some(first) = ids.get(&item.id[..]) {
+                diagnostics.push(Diagnostic::error(format!("/items/{}/id", i), "duplicate-id",
+                    format!("the id '{}' is already used by /items/{}", item.id, first)));
+            } else {
+                ids.insert(&item.id[..], i);
+            }
+
+            let empty = match item.content {
+                Content::Html(ref s) | Content::Text(ref s) => s.is_empty(),
+                Content::Both(ref s, ref t) => s.is_empty() && t.is_empty(),
+            };
+            if empty {
+                diagnostics.push(Diagnostic::warning(format!("/items/{}", i), "empty-content",
+                    "content_html and content_text are both empty"));
+            }
+
+            let dates = [("date_published", &item.date_published), ("date_modified", &item.date_modified)];
+            for &(field, value) in &dates {
+                if let Some(Err(message)) = value.as_ref().map(|d| date::check(d)) {
+                    diagnostics.push(Diagnostic::error(format!("/items/{}/{}", i, field), "invalid-date", message));
+                }
+            }
+
+            if let Some(ts) = item.date_published.as_deref().and_then(date::timestamp) {
+                if let Some((j, prev)) = previous {
+                    if ts > prev {
+                        diagnostics.push(Diagnostic::warning(format!("/items/{}/date_published", i),
+                            "not-reverse-chronological",
+                            format!("the item is newer than /items/{}", j)));
+                    }
+                }
+                previous = Some((i, ts));
+            }
+
+            if let Some(ref attachments) = item.attachments {
+                for (j, attachment) in attachments.iter().enumerate() {
+                    let path = format!("/items/{}/attachments/{}", i, j);
+                    if attachment.mime_type.trim().is_empty() {
+                        diagnostics.push(Diagnostic::error(format!("{}/mime_type", path), "empty-mime-type",
+                            "the attachment mime_type is empty"));
+                    }
+                    if attachment.size_in_bytes == Some(0) {
+                        diagnostics.push(Diagnostic::warning(format!("{}/size_in_bytes", path),
+                            "suspicious-size", "size_in_bytes is 0"));
+                    }
+                    match attachment.duration_in_seconds {
+                        Some(0) => {
+                            diagnostics.push(Diagnostic::warning(format!("{}/duration_in_seconds", path),
+                                "suspicious-duration", "duration_in_seconds is 0"));
+                        },
+                        Some(d) if d > MAX_DURATION => {
+                            diagnostics.push(Diagnostic::warning(format!("{}/duration_in_seconds", path),
+                                "suspicious-duration",
+                                format!("duration_in_seconds is {}, which is longer than a week", d)));
+                        },
+                        _ => {},
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Turns a path like `items[0].attachments[1].url` into a JSON pointer
+/// like `/items/0/attachments/1/url`
+fn pointer(path: &str) -> String {
+    let mut pointer = String::with_capacity(path.len() + 1);
+    for segment in path.split('.') {
+        for part in segment.split('[') {
+            pointer.push('/');
+            pointer.push_str(part.trim_end_matches(']'));
+        }
+    }
+    pointer
+}
+
+/// Checks for an RFC 3986 scheme, e.g. `https:`
+fn has_scheme(url: &str) -> bool {
+    match url.find(':') {
+        Some(i) => {
+            let scheme = &url[..i];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic()) &&
+                scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        },
+        None => false,
+    }
+}
+
+#[cfg(feature = "url")]
+fn invalid_url(url: &str) -> Option<String> {
+    match Url::parse(url) {
+        Ok(_) => None,
+        Err(::url::ParseError::RelativeUrlWithoutBase) => None,
+        Err(e) => Some(format!("'{}' is not a valid URL: {}", url, e)),
+    }
+}
+
+#[cfg(not(feature = "url"))]
+fn invalid_url(_url: &str) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::default::Default;
+    use item::Item;
+    use feed::Attachment;
+
+    fn item(id: &str, date: &str) -> Item {
+        Item {
+            id: id.to_string(),
+            content: Content::Text("hello".to_string()),
+            date_published: Some(date.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn codes(feed: &Feed) -> Vec<(&'static str, String)> {
+        feed.validate().into_iter().map(|d| (d.code, d.path)).collect()
+    }
+
+    #[test]
+    fn valid_feed() {
+        let feed = Feed {
+            title: "my feed".to_string(),
+            feed_url: Some("https://example.com/feed.json".to_string()),
+            next_url: Some("https://example.com/feed.json?page=2".to_string()),
+            items: vec![
+                item("2", "2017-01-02T00:00:00Z"),
+                item("1", "2017-01-01T00:00:00Z"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(feed.validate(), vec![]);
+    }
+
+    #[test]
+    fn invalid_feed() {
+        let mut feed = Feed {
+            feed_url: Some("https://example.com/feed.json".to_string()),
+            next_url: Some("https://example.com/feed.json".to_string()),
+            icon: Some("/icon.png".to_string()),
+            items: vec![
+                item("1", "2017-01-01T00:00:00Z"),
+                item("1", "2017-01-02T00:00:00Z"),
+                Item { content: Content::Html("".to_string()), ..item("", "2016-01-01T00:00:00Z") },
+                item("3", "yesterday"),
+            ],
+            ..Default::default()
+        };
+        let attachment: Attachment = ::serde_json::from_str(
+            r#"{"url":"https://example.com/a.mp3","mime_type":"audio/mpeg","size_in_bytes":0,"duration_in_seconds":604801}"#
+        ).unwrap();
+        feed.items[0].attachments = Some(vec![attachment]);
+        assert_eq!(
+            codes(&feed),
+            vec![
+                ("empty-title", "/title".to_string()),
+                ("next-url-is-feed-url", "/next_url".to_string()),
+                ("relative-url", "/icon".to_string()),
+                ("suspicious-size", "/items/0/attachments/0/size_in_bytes".to_string()),
+                ("suspicious-duration", "/items/0/attachments/0/duration_in_seconds".to_string()),
+                ("duplicate-id", "/items/1/id".to_string()),
+                ("not-reverse-chronological", "/items/1/date_published".to_string()),
+                ("empty-id", "/items/2/id".to_string()),
+                ("empty-content", "/items/2".to_string()),
+                ("invalid-date", "/items/3/date_published".to_string()),
+            ]
+        );
+        assert!(feed.validate()[0].is_error());
+    }
+
+    #[test]
+    fn pointers() {
+        assert_eq!(pointer("title"), "/title");
+        assert_eq!(pointer("items[0].attachments[12].url"), "/items/0/attachments/12/url");
+        assert_eq!(pointer("items[3].authors[0].avatar"), "/items/3/authors/0/avatar");
+    }
+}
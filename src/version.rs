@@ -0,0 +1,117 @@
+use std::fmt;
+
+use serde::ser::{self, Serialize, Serializer};
+use serde::de::{Deserialize, Deserializer};
+
+/// The version URL for JSON Feed version 1
+pub const VERSION_1: &str = "https://jsonfeed.org/version/1";
+/// The version URL for JSON Feed version 1.1
+pub const VERSION_1_1: &str = "https://jsonfeed.org/version/1.1";
+
+/// Represents the `version` attribute of a feed
+///
+/// Versions this crate doesn't know about are kept in `Unknown`, but
+/// they are rejected by `from_str` and friends, and can't be serialized.
+///
+/// # Examples
+///
+/// ```rust
+/// # extern crate jsonfeed;
+/// # use jsonfeed::Version;
+/// # fn main() {
+/// assert_eq!(Version::from("https://jsonfeed.org/version/1.1"), Version::V1_1);
+/// assert_eq!(Version::V1.as_str(), "https://jsonfeed.org/version/1");
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Version {
+    #[default]
+    V1,
+    V1_1,
+    Unknown(String),
+}
+
+impl Version {
+    /// The version URL, as it appears in a feed
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Version::V1 => VERSION_1,
+            Version::V1_1 => VERSION_1_1,
+            Version::Unknown(ref s) => s,
+        }
+    }
+
+    /// Returns false for `Version::Unknown`
+    pub fn is_known(&self) -> bool {
+        !matches!(*self, Version::Unknown(_))
+    }
+}
+
+impl<'a> From<&'a str> for Version {
+    fn from(s: &'a str) -> Version {
+        match s {
+            VERSION_1 => Version::V1,
+            VERSION_1_1 => Version::V1_1,
+            _ => Version::Unknown(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for Version {
+    fn from(s: String) -> Version {
+        match Version::from(&s[..]) {
+            Version::Unknown(_) => Version::Unknown(s),
+            v => v,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        match *self {
+            Version::Unknown(ref s) => {
+                Err(ser::Error::custom(format!("unsupported JSON Feed version '{}'", s)))
+            },
+            _ => serializer.serialize_str(self.as_str()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        String::deserialize(deserializer).map(Version::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn serialize_version() {
+        assert_eq!(
+            serde_json::to_string(&Version::V1_1).unwrap(),
+            r#""https://jsonfeed.org/version/1.1""#
+        );
+        assert!(serde_json::to_string(&Version::from("https://jsonfeed.org/version/2")).is_err());
+    }
+
+    #[test]
+    fn deserialize_version() {
+        let v: Version = serde_json::from_str(r#""https://jsonfeed.org/version/1""#).unwrap();
+        assert_eq!(v, Version::V1);
+        let v: Version = serde_json::from_str(r#""https://jsonfeed.org/version/2""#).unwrap();
+        assert_eq!(v, Version::Unknown("https://jsonfeed.org/version/2".to_string()));
+    }
+}